@@ -1,3 +1,4 @@
+use crate::todo::SortBy;
 use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
@@ -7,22 +8,73 @@ pub struct Cli {
     #[arg(short, long, default_value = "todo_list.json")]
     pub file: String,
 
+    // Storage backend; inferred from the file extension when omitted
+    #[arg(long, value_parser=["json", "sqlite"])]
+    pub backend: Option<String>,
+
+    // Config file declaring user-defined attributes; defaults to
+    // $TODO_CONFIG or ~/.config/todo/config.toml
+    #[arg(long, global = true)]
+    pub config: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
+// Values accepted wherever a status is given on the command line
+const STATUSES: [&str; 5] = ["pending", "in-progress", "waiting", "done", "cancelled"];
+
+// Accepts the built-in sort keys and `uda:<name>`; the attribute itself is
+// checked against the config once it has been loaded
+fn sort_key(s: &str) -> Result<String, String> {
+    s.parse::<SortBy>()
+        .map(|_| s.to_string())
+        .map_err(|_| "expected due, priority, due+priority or uda:<name>".to_string())
+}
+
 #[derive(Subcommand)]
 pub enum Commands {
     Add {
         title: String,
         #[arg(long)]
         due_date: Option<String>,
+        // When work can start
+        #[arg(long)]
+        scheduled: Option<String>,
+        // Keep the todo out of `list` until this date
+        #[arg(long)]
+        wait: Option<String>,
         #[arg(long, value_parser=["high", "medium", "low"])]
         priority: Option<String>,
+        // Expected effort, e.g. 90m or 2h
+        #[arg(long)]
+        estimate: Option<String>,
         #[arg(long)]
         description: Option<String>,
         #[arg(long, value_delimiter = ',')]
         tags: Option<Vec<String>>,
+        // Dotted project such as work.backend; subtasks default to their
+        // parent's project
+        #[arg(long)]
+        project: Option<String>,
+        // User responsible for the todo
+        #[arg(long)]
+        assignee: Option<String>,
+        // e.g. "1h before" the due date or "tomorrow 9:00"; repeatable
+        #[arg(long)]
+        remind: Vec<String>,
+        // Make the new todo a subtask of another one
+        #[arg(long)]
+        parent: Option<u32>,
+        // Ids of todos that must be completed first
+        #[arg(long, value_delimiter = ',')]
+        depends_on: Option<Vec<u32>>,
+        // e.g. daily, weekly, "every 2 weeks", "every monday"
+        #[arg(long)]
+        repeat: Option<String>,
+        // Set a user-defined attribute, e.g. --set points=3; repeatable
+        #[arg(long = "set", value_name = "KEY=VALUE")]
+        set: Vec<String>,
     },
     Edit {
         id: u32,
@@ -32,31 +84,107 @@ pub enum Commands {
         description: Option<String>,
         #[arg(long)]
         due_date: Option<String>,
+        #[arg(long)]
+        scheduled: Option<String>,
+        #[arg(long)]
+        wait: Option<String>,
         #[arg(long, value_parser=["high", "medium", "low"])]
         priority: Option<String>,
+        // "none" removes the estimate
+        #[arg(long)]
+        estimate: Option<String>,
         #[arg(long, value_delimiter = ',')]
         tags: Option<Vec<String>>,
+        #[arg(long)]
+        parent: Option<u32>,
+        #[arg(long, value_delimiter = ',')]
+        depends_on: Option<Vec<u32>>,
+        // Change the repeat rule; "none" stops repeating
+        #[arg(long)]
+        repeat: Option<String>,
+        #[arg(long, value_parser=STATUSES)]
+        status: Option<String>,
+        // "none" unassigns the todo
+        #[arg(long)]
+        assignee: Option<String>,
+        // Replace the reminders; "none" removes them
+        #[arg(long)]
+        remind: Vec<String>,
+        // Set a user-defined attribute; an empty value removes it
+        #[arg(long = "set", value_name = "KEY=VALUE")]
+        set: Vec<String>,
     },
     Toggle {
         id: u32,
     },
+    // Mark a todo as in progress
+    Start {
+        id: u32,
+    },
+    // Close a todo without completing it
+    Cancel {
+        id: u32,
+    },
+    // Move a todo to the trash
     Delete {
         id: u32,
     },
+    // Bring a trashed todo back
+    Restore {
+        id: u32,
+    },
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommands,
+    },
     ClearList,
+    // Revert the most recent changes
+    Undo {
+        #[arg(default_value_t = 1)]
+        steps: usize,
+    },
+    // Re-apply changes reverted by undo
+    Redo {
+        #[arg(default_value_t = 1)]
+        steps: usize,
+    },
     List {
         #[arg(long, group = "order", conflicts_with = "desc")]
         asc: bool,
         #[arg(long, group = "order", conflicts_with = "asc")]
         desc: bool,
-        #[arg(long, value_parser=["due", "priority", "due+priority"])]
+        #[arg(long, value_parser=sort_key)]
         sort_by: Option<String>,
         #[arg(long, group = "filter-status")]
         only_complete: bool,
         #[arg(long, group = "filter-status")]
         only_pending: bool,
+        // Only todos in any of these statuses
+        #[arg(long, value_delimiter = ',', value_parser=STATUSES)]
+        status: Vec<String>,
         #[arg(long, value_parser=["high", "medium", "low"])]
         priority: Option<String>,
+        // Only todos with all of these tags
+        #[arg(long, value_delimiter = ',')]
+        tag: Vec<String>,
+        // Only todos with at least one of these tags
+        #[arg(long, value_delimiter = ',')]
+        any_tag: Vec<String>,
+        // Hide todos with any of these tags
+        #[arg(long, value_delimiter = ',')]
+        no_tag: Vec<String>,
+        // Only todos without tags
+        #[arg(long, conflicts_with_all = ["tag", "any_tag"])]
+        untagged: bool,
+        // Only todos in this project or its sub-projects
+        #[arg(long)]
+        project: Option<String>,
+        // Only todos assigned to the current user
+        #[arg(long, conflicts_with = "assignee")]
+        mine: bool,
+        // Only todos assigned to this user
+        #[arg(long)]
+        assignee: Option<String>,
         #[arg(long, group = "filter-time")]
         overdue: bool,
         #[arg(long, group = "filter-time")]
@@ -65,6 +193,141 @@ pub enum Commands {
         due_tomorrow: bool,
         #[arg(long, group = "filter-time")]
         due_within: Option<i64>,
+        #[arg(long)]
+        scheduled_today: bool,
+        // Only todos with a note containing this text
+        #[arg(long)]
+        note_contains: Option<String>,
+        // Show only todos hidden by a future wait date
+        #[arg(long)]
+        waiting: bool,
+        // Pending todos waiting on unfinished dependencies
+        #[arg(long, group = "filter-deps")]
+        blocked: bool,
+        // Pending todos whose dependencies are all done
+        #[arg(long, group = "filter-deps")]
+        ready: bool,
+        // Only todos completed on/after this date
+        #[arg(long)]
+        completed_since: Option<String>,
+        // Only todos completed before this date
+        #[arg(long)]
+        completed_before: Option<String>,
+        // Browse the archive instead of the main list
+        #[arg(long)]
+        archived: bool,
+        // Filter expression, e.g. 'priority>=medium and (tag:work or due<friday) and not done'
+        #[arg(long = "where", value_name = "QUERY")]
+        where_clause: Option<String>,
+        // Condition on a user-defined attribute, e.g. points>=3; repeatable
+        #[arg(long, value_name = "EXPR")]
+        uda: Vec<String>,
+        // User-defined attributes shown as extra columns
+        #[arg(long, value_delimiter = ',')]
+        columns: Option<Vec<String>>,
+    },
+    // Remind about a todo again after a while, e.g. 2h or 1d
+    Snooze {
+        id: u32,
+        duration: String,
+    },
+    // List upcoming reminders
+    Reminders {
+        // Print reminders that fired since the last check, once each
+        #[arg(long)]
+        pending: bool,
+    },
+    // Give a todo to a user; defaults to you, "none" unassigns it
+    Assign {
+        id: u32,
+        user: Option<String>,
+    },
+    // List every tag with its number of open and done todos
+    Tags,
+    // List projects with their number of open and total todos
+    Projects,
+    // Put a todo and its subtasks into another project ("none" to remove)
+    Move {
+        id: u32,
+        #[arg(long)]
+        project: String,
+    },
+    // Move done and cancelled todos to the archive file
+    Archive {
+        #[arg(long)]
+        completed_before: Option<String>,
+    },
+    Unarchive {
+        id: u32,
+    },
+    // Show every recorded change to a todo
+    History {
+        id: u32,
+    },
+    // Show all details of a todo, including tracked time and notes
+    Show {
+        id: u32,
+    },
+    // Append a timestamped note to a todo
+    Note {
+        id: u32,
+        text: String,
+    },
+    // Link a URL or an existing local file to a todo
+    Attach {
+        id: u32,
+        target: String,
+    },
+    // Open the first attachment of a todo
+    Open {
+        id: u32,
+    },
+    Timer {
+        #[command(subcommand)]
+        command: TimerCommands,
+    },
+    Report {
+        #[command(subcommand)]
+        command: ReportCommands,
+    },
+}
+
+impl Commands {
+    // Whether the command reads or writes the archive store
+    pub fn uses_archive(&self) -> bool {
+        matches!(
+            self,
+            Commands::Archive { .. }
+                | Commands::Unarchive { .. }
+                | Commands::List { archived: true, .. }
+        )
+    }
+}
+
+#[derive(Subcommand)]
+pub enum TrashCommands {
+    List,
+    // Permanently delete trashed todos, e.g. `--older-than 30d`
+    Purge {
+        #[arg(long)]
+        older_than: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TimerCommands {
+    // Start timing a todo; only one timer can run at a time
+    Start { id: u32 },
+    // Stop the running timer and log the time on its todo
+    Stop,
+}
+
+#[derive(Subcommand)]
+pub enum ReportCommands {
+    // Time tracked per todo, e.g. `--since "last week"`
+    Time {
+        #[arg(long)]
+        since: Option<String>,
     },
 }
 
@@ -88,6 +351,19 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
 
         assert_eq!(cli.file, "custom.json");
+        assert_eq!(cli.backend, None);
+    }
+
+    #[test]
+    fn test_cli_backend_flag() {
+        let args = vec!["todo", "--file", "todos.db", "--backend", "sqlite", "list"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        assert_eq!(cli.file, "todos.db");
+        assert_eq!(cli.backend, Some("sqlite".to_string()));
+
+        let args = vec!["todo", "--backend", "csv", "list"];
+        assert!(Cli::try_parse_from(args).is_err());
     }
 
     #[test]
@@ -99,11 +375,31 @@ mod tests {
             Commands::Add {
                 title,
                 due_date,
+                scheduled,
+                wait,
                 priority,
+                estimate,
                 description,
                 tags,
+                parent,
+                depends_on,
+                repeat,
+                set,
+                project,
+                assignee,
+                remind,
             } => {
                 assert_eq!(title, "Buy groceries");
+                assert!(remind.is_empty());
+                assert_eq!(assignee, None);
+                assert!(set.is_empty());
+                assert_eq!(project, None);
+                assert_eq!(scheduled, None);
+                assert_eq!(wait, None);
+                assert_eq!(estimate, None);
+                assert_eq!(repeat, None);
+                assert_eq!(parent, None);
+                assert_eq!(depends_on, None);
                 assert_eq!(due_date, None);
                 assert_eq!(priority, None);
                 assert_eq!(description, None);
@@ -137,6 +433,7 @@ mod tests {
                 priority,
                 description,
                 tags,
+                ..
             } => {
                 assert_eq!(title, "Complete project");
                 assert_eq!(due_date, Some("2025-12-25".to_string()));
@@ -178,6 +475,7 @@ mod tests {
                 due_date,
                 priority,
                 tags,
+                ..
             } => {
                 assert_eq!(id, 1);
                 assert_eq!(title, Some("New title".to_string()));
@@ -190,6 +488,114 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parent_option() {
+        let args = vec!["todo", "add", "Write tests", "--parent", "3"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Add {
+                parent: Some(3),
+                ..
+            }
+        ));
+
+        let args = vec!["todo", "edit", "4", "--parent", "1"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Edit {
+                id: 4,
+                parent: Some(1),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_dependency_options() {
+        let args = vec!["todo", "add", "Ship", "--depends-on", "3,4"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Add { depends_on, .. } => assert_eq!(depends_on, Some(vec![3, 4])),
+            _ => panic!("Expected Add command"),
+        }
+
+        let args = vec!["todo", "edit", "5", "--depends-on", "x"];
+        assert!(Cli::try_parse_from(args).is_err());
+
+        let cli = Cli::try_parse_from(vec!["todo", "list", "--ready"]).unwrap();
+        assert!(matches!(cli.command, Commands::List { ready: true, .. }));
+        let args = vec!["todo", "list", "--ready", "--blocked"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_repeat_option() {
+        let args = vec!["todo", "add", "Standup", "--repeat", "every monday"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Add { repeat, .. } => assert_eq!(repeat, Some("every monday".to_string())),
+            _ => panic!("Expected Add command"),
+        }
+
+        let args = vec!["todo", "edit", "2", "--repeat", "none"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Edit { repeat, .. } => assert_eq!(repeat, Some("none".to_string())),
+            _ => panic!("Expected Edit command"),
+        }
+    }
+
+    #[test]
+    fn test_uda_options() {
+        let args = vec![
+            "todo",
+            "add",
+            "Story",
+            "--set",
+            "points=3",
+            "--set",
+            "size=M",
+            "--config",
+            "team.toml",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert_eq!(cli.config, Some("team.toml".to_string()));
+        match cli.command {
+            Commands::Add { set, .. } => assert_eq!(set, vec!["points=3", "size=M"]),
+            _ => panic!("Expected Add command"),
+        }
+
+        let args = vec![
+            "todo",
+            "list",
+            "--uda",
+            "points>=3",
+            "--columns",
+            "points,size",
+            "--sort-by",
+            "uda:points",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::List {
+                uda,
+                columns,
+                sort_by,
+                ..
+            } => {
+                assert_eq!(uda, vec!["points>=3"]);
+                assert_eq!(
+                    columns,
+                    Some(vec!["points".to_string(), "size".to_string()])
+                );
+                assert_eq!(sort_by, Some("uda:points".to_string()));
+            }
+            _ => panic!("Expected List command"),
+        }
+    }
+
     #[test]
     fn test_toggle_command() {
         let args = vec!["todo", "toggle", "42"];
@@ -203,6 +609,66 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_schedule_options() {
+        let args = vec![
+            "todo",
+            "add",
+            "Taxes",
+            "--scheduled",
+            "monday",
+            "--wait",
+            "01-03-2030",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Add {
+                scheduled, wait, ..
+            } => {
+                assert_eq!(scheduled, Some("monday".to_string()));
+                assert_eq!(wait, Some("01-03-2030".to_string()));
+            }
+            _ => panic!("Expected Add command"),
+        }
+
+        let args = vec!["todo", "list", "--waiting", "--scheduled-today"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::List {
+                waiting: true,
+                scheduled_today: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_status_commands() {
+        let cli = Cli::try_parse_from(vec!["todo", "start", "3"]).unwrap();
+        assert!(matches!(cli.command, Commands::Start { id: 3 }));
+
+        let cli = Cli::try_parse_from(vec!["todo", "cancel", "3"]).unwrap();
+        assert!(matches!(cli.command, Commands::Cancel { id: 3 }));
+
+        let args = vec!["todo", "edit", "3", "--status", "waiting"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Edit { status, .. } => assert_eq!(status, Some("waiting".to_string())),
+            _ => panic!("Expected Edit command"),
+        }
+
+        let args = vec!["todo", "list", "--status", "in-progress,waiting"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::List { status, .. } => assert_eq!(status, vec!["in-progress", "waiting"]),
+            _ => panic!("Expected List command"),
+        }
+
+        let args = vec!["todo", "list", "--status", "later"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
     #[test]
     fn test_delete_command() {
         let args = vec!["todo", "delete", "5"];
@@ -224,6 +690,143 @@ mod tests {
         assert!(matches!(cli.command, Commands::ClearList));
     }
 
+    #[test]
+    fn test_trash_commands() {
+        let cli = Cli::try_parse_from(vec!["todo", "restore", "4"]).unwrap();
+        assert!(matches!(cli.command, Commands::Restore { id: 4 }));
+
+        let cli = Cli::try_parse_from(vec!["todo", "trash", "list"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Trash {
+                command: TrashCommands::List
+            }
+        ));
+
+        let args = vec!["todo", "trash", "purge", "--older-than", "30d"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Trash {
+                command: TrashCommands::Purge { older_than },
+            } => assert_eq!(older_than, Some("30d".to_string())),
+            _ => panic!("Expected Trash Purge command"),
+        }
+    }
+
+    #[test]
+    fn test_archive_commands() {
+        let args = vec!["todo", "archive", "--completed-before", "2025-01-01"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert!(cli.command.uses_archive());
+        match cli.command {
+            Commands::Archive { completed_before } => {
+                assert_eq!(completed_before, Some("2025-01-01".to_string()));
+            }
+            _ => panic!("Expected Archive command"),
+        }
+
+        let cli = Cli::try_parse_from(vec!["todo", "unarchive", "2"]).unwrap();
+        assert!(matches!(cli.command, Commands::Unarchive { id: 2 }));
+
+        let cli = Cli::try_parse_from(vec!["todo", "list", "--archived"]).unwrap();
+        assert!(cli.command.uses_archive());
+        let cli = Cli::try_parse_from(vec!["todo", "list"]).unwrap();
+        assert!(!cli.command.uses_archive());
+    }
+
+    #[test]
+    fn test_time_tracking_commands() {
+        let cli = Cli::try_parse_from(vec!["todo", "timer", "start", "3"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Timer {
+                command: TimerCommands::Start { id: 3 }
+            }
+        ));
+
+        let cli = Cli::try_parse_from(vec!["todo", "timer", "stop"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Timer {
+                command: TimerCommands::Stop
+            }
+        ));
+
+        let args = vec!["todo", "report", "time", "--since", "last week"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Report {
+                command: ReportCommands::Time { since },
+            } => assert_eq!(since, Some("last week".to_string())),
+            _ => panic!("Expected Report command"),
+        }
+
+        let args = vec!["todo", "edit", "3", "--estimate", "2h"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Edit { estimate, .. } => assert_eq!(estimate, Some("2h".to_string())),
+            _ => panic!("Expected Edit command"),
+        }
+
+        let cli = Cli::try_parse_from(vec!["todo", "show", "3"]).unwrap();
+        assert!(matches!(cli.command, Commands::Show { id: 3 }));
+    }
+
+    #[test]
+    fn test_attachment_commands() {
+        let args = vec!["todo", "attach", "2", "https://example.com/issues/7"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::Attach { id, target } => {
+                assert_eq!(id, 2);
+                assert_eq!(target, "https://example.com/issues/7");
+            }
+            _ => panic!("Expected Attach command"),
+        }
+
+        let cli = Cli::try_parse_from(vec!["todo", "open", "2"]).unwrap();
+        assert!(matches!(cli.command, Commands::Open { id: 2 }));
+    }
+
+    #[test]
+    fn test_note_commands() {
+        let cli = Cli::try_parse_from(vec!["todo", "note", "3", "Called back"]).unwrap();
+        match cli.command {
+            Commands::Note { id, text } => {
+                assert_eq!(id, 3);
+                assert_eq!(text, "Called back");
+            }
+            _ => panic!("Expected Note command"),
+        }
+
+        let args = vec!["todo", "list", "--note-contains", "invoice"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        match cli.command {
+            Commands::List { note_contains, .. } => {
+                assert_eq!(note_contains, Some("invoice".to_string()))
+            }
+            _ => panic!("Expected List command"),
+        }
+    }
+
+    #[test]
+    fn test_history_command() {
+        let cli = Cli::try_parse_from(vec!["todo", "history", "7"]).unwrap();
+        assert!(matches!(cli.command, Commands::History { id: 7 }));
+    }
+
+    #[test]
+    fn test_undo_redo_commands() {
+        let cli = Cli::try_parse_from(vec!["todo", "undo"]).unwrap();
+        assert!(matches!(cli.command, Commands::Undo { steps: 1 }));
+
+        let cli = Cli::try_parse_from(vec!["todo", "undo", "3"]).unwrap();
+        assert!(matches!(cli.command, Commands::Undo { steps: 3 }));
+
+        let cli = Cli::try_parse_from(vec!["todo", "redo", "2"]).unwrap();
+        assert!(matches!(cli.command, Commands::Redo { steps: 2 }));
+    }
+
     #[test]
     fn test_list_command_basic() {
         let args = vec!["todo", "list"];
@@ -236,17 +839,51 @@ mod tests {
                 sort_by,
                 only_complete,
                 only_pending,
+                status,
                 priority,
                 overdue,
                 due_today,
                 due_tomorrow,
                 due_within,
+                scheduled_today,
+                note_contains,
+                waiting,
+                completed_since,
+                completed_before,
+                blocked,
+                ready,
+                archived,
+                uda,
+                columns,
+                project,
+                mine,
+                assignee,
+                where_clause,
+                tag,
+                any_tag,
+                no_tag,
+                untagged,
             } => {
+                assert!(tag.is_empty() && any_tag.is_empty() && no_tag.is_empty());
+                assert!(!untagged);
+                assert_eq!(where_clause, None);
+                assert_eq!(project, None);
+                assert!(!mine);
+                assert_eq!(assignee, None);
+                assert!(uda.is_empty());
+                assert_eq!(columns, None);
+                assert!(!scheduled_today && !waiting);
+                assert_eq!(note_contains, None);
+                assert!(!archived);
+                assert!(!blocked && !ready);
+                assert_eq!(completed_since, None);
+                assert_eq!(completed_before, None);
                 assert!(!asc);
                 assert!(!desc);
                 assert_eq!(sort_by, None);
                 assert!(!only_complete);
                 assert!(!only_pending);
+                assert!(status.is_empty());
                 assert_eq!(priority, None);
                 assert!(!overdue);
                 assert!(!due_today);
@@ -257,6 +894,127 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_project_commands() {
+        let args = vec!["todo", "add", "Fix login", "--project", "work.backend"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Add { project: Some(ref p), .. } if p == "work.backend"
+        ));
+
+        let cli = Cli::try_parse_from(vec!["todo", "move", "3", "--project", "home"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Move { id: 3, ref project } if project == "home"
+        ));
+        assert!(Cli::try_parse_from(vec!["todo", "move", "3"]).is_err());
+        assert!(matches!(
+            Cli::try_parse_from(vec!["todo", "projects"])
+                .unwrap()
+                .command,
+            Commands::Projects
+        ));
+    }
+
+    #[test]
+    fn test_tag_filters() {
+        let args = vec![
+            "todo",
+            "list",
+            "--tag",
+            "work,urgent",
+            "--any-tag",
+            "a",
+            "--any-tag",
+            "b",
+            "--no-tag",
+            "later",
+        ];
+        match Cli::try_parse_from(args).unwrap().command {
+            Commands::List {
+                tag,
+                any_tag,
+                no_tag,
+                ..
+            } => {
+                assert_eq!(tag, vec!["work", "urgent"]);
+                assert_eq!(any_tag, vec!["a", "b"]);
+                assert_eq!(no_tag, vec!["later"]);
+            }
+            _ => panic!("Expected List command"),
+        }
+
+        assert!(Cli::try_parse_from(vec!["todo", "list", "--untagged", "--tag", "x"]).is_err());
+        assert!(matches!(
+            Cli::try_parse_from(vec!["todo", "tags"]).unwrap().command,
+            Commands::Tags
+        ));
+    }
+
+    #[test]
+    fn test_where_option() {
+        let args = vec![
+            "todo",
+            "list",
+            "--where",
+            "tag:work and not done",
+            "--overdue",
+        ];
+        match Cli::try_parse_from(args).unwrap().command {
+            Commands::List {
+                where_clause,
+                overdue,
+                ..
+            } => {
+                assert_eq!(where_clause, Some("tag:work and not done".to_string()));
+                assert!(overdue);
+            }
+            _ => panic!("Expected List command"),
+        }
+    }
+
+    #[test]
+    fn test_reminder_commands() {
+        let args = vec![
+            "todo",
+            "add",
+            "Dentist",
+            "--remind",
+            "1h before",
+            "--remind",
+            "tomorrow 9:00",
+        ];
+        match Cli::try_parse_from(args).unwrap().command {
+            Commands::Add { remind, .. } => assert_eq!(remind, vec!["1h before", "tomorrow 9:00"]),
+            _ => panic!("Expected Add command"),
+        }
+
+        let cli = Cli::try_parse_from(vec!["todo", "snooze", "4", "2h"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Snooze { id: 4, ref duration } if duration == "2h"
+        ));
+        let cli = Cli::try_parse_from(vec!["todo", "reminders", "--pending"]).unwrap();
+        assert!(matches!(cli.command, Commands::Reminders { pending: true }));
+    }
+
+    #[test]
+    fn test_assignee_options() {
+        let cli = Cli::try_parse_from(vec!["todo", "assign", "2", "alice"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Assign { id: 2, user: Some(ref u) } if u == "alice"
+        ));
+        let cli = Cli::try_parse_from(vec!["todo", "assign", "2"]).unwrap();
+        assert!(matches!(cli.command, Commands::Assign { user: None, .. }));
+
+        let cli = Cli::try_parse_from(vec!["todo", "list", "--mine"]).unwrap();
+        assert!(matches!(cli.command, Commands::List { mine: true, .. }));
+        let args = vec!["todo", "list", "--mine", "--assignee", "bob"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
     #[test]
     fn test_list_command_with_options() {
         let args = vec![
@@ -284,6 +1042,7 @@ mod tests {
                 due_today,
                 due_tomorrow,
                 due_within,
+                ..
             } => {
                 assert!(asc);
                 assert!(!desc);
@@ -366,6 +1125,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_list_completed_range() {
+        let args = vec![
+            "todo",
+            "list",
+            "--completed-since",
+            "last week",
+            "--completed-before",
+            "2025-06-01",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::List {
+                completed_since,
+                completed_before,
+                ..
+            } => {
+                assert_eq!(completed_since, Some("last week".to_string()));
+                assert_eq!(completed_before, Some("2025-06-01".to_string()));
+            }
+            _ => panic!("Expected List command"),
+        }
+    }
+
     #[test]
     fn test_due_within_numeric_value() {
         let args = vec!["todo", "list", "--due-within", "7"];
@@ -1,64 +1,289 @@
-use crate::cli::Commands;
-use crate::display::display_todos;
-use crate::todo::{ListQuery, SortBy, TodoItem, TodoManager, TodoResult};
+use crate::cli::{Commands, ReportCommands, TimerCommands, TrashCommands};
+use crate::display::{
+    display_history, display_projects, display_reminders, display_tags, display_time_report,
+    display_todo_details, display_todo_table,
+};
+use crate::todo::{
+    normalize_tags, parse_project, project_counts, tag_counts, FilterExpr, ListQuery, Reminder,
+    SortBy, Status, TodoError, TodoFields, TodoItem, TodoManager, TodoResult, UdaFilter, Udas,
+};
+use crate::utils::{open_target, parse_duration, parse_past_date};
+use chrono::Local;
 use std::cmp::Ordering;
 use std::str::FromStr;
 
+// Each command is saved in one go, together with its undo journal entry
 pub fn handle_commands(command: Commands, manager: &mut TodoManager) -> TodoResult<()> {
+    manager.batch(|manager| run_command(command, manager))
+}
+
+fn run_command(command: Commands, manager: &mut TodoManager) -> TodoResult<()> {
     match command {
         Commands::Add {
             title,
             description,
             due_date,
+            scheduled,
+            wait,
             priority,
+            estimate,
             tags,
+            parent,
+            depends_on,
+            repeat,
+            set,
+            project,
+            assignee,
+            remind,
         } => {
-            manager.add_todo(title, description, due_date, priority.as_deref(), tags)?;
+            manager.add_todo_with(TodoFields {
+                title: Some(title),
+                description,
+                due_date,
+                scheduled,
+                wait,
+                priority,
+                estimate,
+                tags,
+                parent,
+                depends_on,
+                repeat,
+                set,
+                project,
+                assignee,
+                remind,
+                ..Default::default()
+            })?;
         }
         Commands::Edit {
             id,
             title,
             description,
             due_date,
+            scheduled,
+            wait,
             priority,
+            estimate,
             tags,
+            parent,
+            depends_on,
+            repeat,
+            status,
+            assignee,
+            remind,
+            set,
         } => {
-            manager.edit_todo(
+            manager.edit_todo_with(
                 id,
-                title,
-                description,
-                due_date.as_deref(),
-                priority.as_deref(),
-                tags,
+                TodoFields {
+                    title,
+                    description,
+                    due_date,
+                    scheduled,
+                    wait,
+                    priority,
+                    estimate,
+                    tags,
+                    parent,
+                    depends_on,
+                    repeat,
+                    status,
+                    assignee,
+                    remind,
+                    set,
+                    ..Default::default()
+                },
             )?;
         }
         Commands::Toggle { id } => {
             manager.toggle_todo(id)?;
         }
+        Commands::Start { id } => {
+            manager.start_todo(id)?;
+        }
+        Commands::Cancel { id } => {
+            manager.cancel_todo(id)?;
+        }
+        Commands::Snooze { id, duration } => {
+            manager.snooze(id, &duration)?;
+        }
+        Commands::Reminders { pending } => {
+            let reminders = match pending {
+                true => manager.deliver_reminders(Local::now().naive_local())?,
+                false => manager.upcoming_reminders(),
+            };
+            let mut reminders: Vec<(&TodoItem, Reminder)> = reminders
+                .into_iter()
+                .filter_map(|(id, r)| manager.find_todo(id).ok().map(|t| (t, r)))
+                .collect();
+            reminders.sort_by_key(|(_, r)| r.at);
+            display_reminders(&reminders);
+        }
+        Commands::Assign { id, user } => {
+            manager.assign(id, user.as_deref())?;
+        }
+        Commands::Tags => {
+            let todos: Vec<TodoItem> = manager
+                .todos
+                .iter()
+                .filter(|t| !t.is_trashed())
+                .cloned()
+                .collect();
+            display_tags(&tag_counts(&todos));
+        }
+        Commands::Projects => {
+            let todos: Vec<TodoItem> = manager
+                .todos
+                .iter()
+                .filter(|t| !t.is_trashed())
+                .cloned()
+                .collect();
+            display_projects(&project_counts(&todos));
+        }
+        Commands::Move { id, project } => {
+            manager.move_todo(id, &project)?;
+        }
         Commands::Delete { id } => {
             manager.delete_todo(id)?;
         }
+        Commands::Archive { completed_before } => {
+            let completed_before = parse_past_date(completed_before.as_deref())?;
+            manager.archive_completed(completed_before)?;
+        }
+        Commands::Unarchive { id } => {
+            manager.unarchive(id)?;
+        }
+        Commands::History { id } => {
+            display_history(manager.find_todo(id)?);
+        }
+        Commands::Show { id } => {
+            let timer = manager.active_timer()?;
+            display_todo_details(manager.find_todo(id)?, timer.as_ref());
+        }
+        Commands::Note { id, text } => {
+            manager.add_note(id, &text)?;
+        }
+        Commands::Attach { id, target } => {
+            manager.attach(id, &target)?;
+        }
+        Commands::Open { id } => {
+            let attachment = manager
+                .find_todo(id)?
+                .attachments()
+                .first()
+                .ok_or(TodoError::NoAttachments { id })?;
+            println!("🔗 Opening {}", attachment.target);
+            open_target(&attachment.target, attachment.is_url())?;
+        }
+        Commands::Timer { command } => match command {
+            TimerCommands::Start { id } => manager.start_timer(id)?,
+            TimerCommands::Stop => {
+                manager.stop_timer()?;
+            }
+        },
+        Commands::Report { command } => match command {
+            ReportCommands::Time { since } => {
+                let since = parse_past_date(since.as_deref())?;
+                let timer = manager.active_timer()?;
+                let todos: Vec<TodoItem> = manager
+                    .todos
+                    .iter()
+                    .filter(|t| !t.is_trashed())
+                    .cloned()
+                    .collect();
+                display_time_report(&todos, since, timer.as_ref());
+            }
+        },
+        Commands::Restore { id } => {
+            manager.restore_todo(id)?;
+        }
+        Commands::Trash { command } => match command {
+            TrashCommands::List => {
+                let query = ListQuery {
+                    trashed: true,
+                    ..Default::default()
+                };
+                handle_list_command(manager, query, &[])?;
+            }
+            TrashCommands::Purge { older_than } => {
+                let older_than = older_than.as_deref().map(parse_duration).transpose()?;
+                manager.purge_trash(older_than)?;
+            }
+        },
         Commands::ClearList => {
             manager.clear_all();
         }
+        Commands::Undo { steps } => {
+            manager.undo(steps)?;
+        }
+        Commands::Redo { steps } => {
+            manager.redo(steps)?;
+        }
         Commands::List {
             asc,
             desc,
             sort_by,
             only_complete,
             only_pending,
+            status,
             priority,
+            tag,
+            any_tag,
+            no_tag,
+            untagged,
+            project,
+            mine,
+            assignee,
             overdue,
             due_today,
             due_tomorrow,
             due_within,
+            scheduled_today,
+            note_contains,
+            waiting,
+            blocked,
+            ready,
+            completed_since,
+            completed_before,
+            archived,
+            where_clause,
+            uda,
+            columns,
         } => {
             let sort_by = match sort_by {
                 Some(s) => SortBy::from_str(&s)?,
                 None => SortBy::Due,
             };
+            let udas = &manager.config().uda;
+            if let SortBy::Uda(key) = &sort_by {
+                if !udas.contains_key(key) {
+                    return Err(TodoError::UnknownUda { key: key.clone() });
+                }
+            }
+            let uda = uda
+                .iter()
+                .map(|expr| UdaFilter::parse(expr, udas))
+                .collect::<TodoResult<Vec<_>>>()?;
+            let expr = where_clause
+                .as_deref()
+                .map(|input| FilterExpr::parse(input, udas))
+                .transpose()?;
+            let columns = columns.unwrap_or_else(|| manager.config().list.columns.clone());
+            if let Some(key) = columns.iter().find(|c| !udas.contains_key(*c)) {
+                return Err(TodoError::UnknownUda { key: key.clone() });
+            }
 
             let priority = TodoManager::parse_priority(priority.as_deref())?;
+            let project = project.as_deref().map(parse_project).transpose()?.flatten();
+            let normalize = |tags: Vec<String>| normalize_tags(tags).unwrap_or_default();
+            let assignee = match mine {
+                true => Some(manager.current_user()?),
+                false => assignee,
+            };
+            let status = status
+                .iter()
+                .map(|s| s.parse::<Status>())
+                .collect::<TodoResult<Vec<_>>>()?;
 
             let query = ListQuery {
                 sort_by,
@@ -66,27 +291,66 @@ pub fn handle_commands(command: Commands, manager: &mut TodoManager) -> TodoResu
                 desc,
                 only_complete,
                 only_pending,
+                status,
                 priority,
+                tags: normalize(tag),
+                any_tags: normalize(any_tag),
+                no_tags: normalize(no_tag),
+                untagged,
+                project,
+                assignee,
                 overdue,
                 due_today,
                 due_tomorrow,
                 due_within,
+                scheduled_today,
+                note_contains,
+                udas: uda,
+                expr,
+                waiting,
+                blocked,
+                ready,
+                completed_since: parse_past_date(completed_since.as_deref())?,
+                completed_before: parse_past_date(completed_before.as_deref())?,
+                trashed: false,
+                archived,
             };
 
-            handle_list_command(&mut manager.todos, query)?;
+            handle_list_command(manager, query, &columns)?;
         }
     }
     Ok(())
 }
 
-fn handle_list_command(todos: &mut [TodoItem], query: ListQuery) -> TodoResult<()> {
+fn handle_list_command(
+    manager: &mut TodoManager,
+    query: ListQuery,
+    columns: &[String],
+) -> TodoResult<()> {
     let ascending = query.asc || !query.desc;
 
-    apply_sorting(todos, query.sort_by, ascending)?;
+    let mut filtered_todos = manager.query(&query)?;
+
+    apply_sorting(
+        &mut filtered_todos,
+        &query.sort_by,
+        ascending,
+        &manager.config().uda,
+    )?;
 
-    let filtered_todos = apply_filter(todos, &query)?;
+    let udas = &manager.config().uda;
+    let columns: Vec<(&str, &str)> = columns
+        .iter()
+        .filter_map(|key| udas.get_key_value(key))
+        .map(|(key, def)| (key.as_str(), def.label(key)))
+        .collect();
 
-    display_todos(&filtered_todos);
+    // Archived subtasks are rolled up against the archive itself
+    if query.archived {
+        display_todo_table(&filtered_todos, &filtered_todos, &columns);
+    } else {
+        display_todo_table(&filtered_todos, &manager.todos, &columns);
+    }
     Ok(())
 }
 
@@ -105,7 +369,12 @@ fn cmp_option<T: Ord>(a: &Option<T>, b: &Option<T>, ascending: bool) -> Ordering
     }
 }
 
-fn apply_sorting(todos: &mut [TodoItem], sort_by: SortBy, ascending: bool) -> TodoResult<()> {
+fn apply_sorting(
+    todos: &mut [TodoItem],
+    sort_by: &SortBy,
+    ascending: bool,
+    udas: &Udas,
+) -> TodoResult<()> {
     use std::cmp::Ordering;
 
     match sort_by {
@@ -125,25 +394,29 @@ fn apply_sorting(todos: &mut [TodoItem], sort_by: SortBy, ascending: bool) -> To
                 }
             });
         }
+        SortBy::Uda(key) => {
+            let def = udas
+                .get(key)
+                .ok_or_else(|| TodoError::UnknownUda { key: key.clone() })?;
+            // Todos without a value go last in either direction
+            todos.sort_by(|a, b| match (a.uda(key), b.uda(key)) {
+                (Some(a_val), Some(b_val)) if ascending => def.compare(a_val, b_val),
+                (Some(a_val), Some(b_val)) => def.compare(b_val, a_val),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            });
+        }
     }
 
     Ok(())
 }
 
-fn apply_filter(todos: &[TodoItem], list_options: &ListQuery) -> TodoResult<Vec<TodoItem>> {
-    let filtered: Vec<TodoItem> = todos
-        .iter()
-        .filter(|todo| list_options.item_passes_filters(todo))
-        .cloned()
-        .collect();
-
-    Ok(filtered)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::cli::Commands;
+    use crate::storage::InMemoryStore;
     use crate::todo::{ListQuery, Priority, SortBy, TodoItem, TodoManager};
     use chrono::NaiveDateTime;
     use std::cmp::Ordering;
@@ -174,8 +447,18 @@ mod tests {
             title: "Test todo".to_string(),
             description: Some("Test description".to_string()),
             due_date: None,
+            scheduled: None,
+            wait: None,
             priority: Some("high".to_string()),
+            estimate: None,
             tags: Some(vec!["work".to_string()]),
+            project: None,
+            assignee: None,
+            remind: vec![],
+            parent: None,
+            depends_on: None,
+            repeat: None,
+            set: vec![],
         };
 
         let result = handle_commands(command, &mut manager);
@@ -200,8 +483,18 @@ mod tests {
             title: Some("Updated".to_string()),
             description: None,
             due_date: None,
+            scheduled: None,
+            wait: None,
             priority: Some("high".to_string()),
+            estimate: None,
             tags: None,
+            parent: None,
+            depends_on: None,
+            repeat: None,
+            status: None,
+            assignee: None,
+            remind: vec![],
+            set: vec![],
         };
 
         let result = handle_commands(command, &mut manager);
@@ -237,7 +530,78 @@ mod tests {
         let result = handle_commands(command, &mut manager);
 
         assert!(result.is_ok());
-        assert_eq!(manager.todos.len(), 0);
+        assert!(manager.todos[0].is_trashed());
+    }
+
+    #[test]
+    fn test_handle_commands_archive() {
+        let mut manager = create_test_manager()
+            .with_archive(Box::new(InMemoryStore::new()))
+            .unwrap();
+        manager
+            .add_todo("Test".to_string(), None, None, None, None)
+            .unwrap();
+        manager.toggle_todo(1).unwrap();
+
+        let archive = Commands::Archive {
+            completed_before: Some("tomorrow".to_string()),
+        };
+        handle_commands(archive, &mut manager).unwrap();
+        assert!(manager.todos.is_empty());
+
+        handle_commands(Commands::Unarchive { id: 1 }, &mut manager).unwrap();
+        assert_eq!(manager.todos.len(), 1);
+    }
+
+    #[test]
+    fn test_handle_commands_projects() {
+        let mut manager = create_test_manager();
+        manager
+            .add_todo("Test".to_string(), None, None, None, None)
+            .unwrap();
+
+        let move_cmd = Commands::Move {
+            id: 1,
+            project: "Work.Backend".to_string(),
+        };
+        handle_commands(move_cmd, &mut manager).unwrap();
+        assert_eq!(manager.todos[0].project(), Some("work.backend"));
+        assert!(handle_commands(Commands::Projects, &mut manager).is_ok());
+    }
+
+    #[test]
+    fn test_handle_commands_history() {
+        let mut manager = create_test_manager();
+        manager
+            .add_todo("Test".to_string(), None, None, None, None)
+            .unwrap();
+
+        assert!(handle_commands(Commands::History { id: 1 }, &mut manager).is_ok());
+        assert!(handle_commands(Commands::History { id: 2 }, &mut manager).is_err());
+    }
+
+    #[test]
+    fn test_handle_commands_trash() {
+        let mut manager = create_test_manager();
+        manager
+            .add_todo("Test".to_string(), None, None, None, None)
+            .unwrap();
+        manager.delete_todo(1).unwrap();
+
+        let list = Commands::Trash {
+            command: TrashCommands::List,
+        };
+        assert!(handle_commands(list, &mut manager).is_ok());
+
+        let purge = Commands::Trash {
+            command: TrashCommands::Purge {
+                older_than: Some("soon".to_string()),
+            },
+        };
+        assert!(handle_commands(purge, &mut manager).is_err());
+
+        handle_commands(Commands::Restore { id: 1 }, &mut manager).unwrap();
+        assert!(!manager.todos[0].is_trashed());
     }
 
     #[test]
@@ -257,6 +621,21 @@ mod tests {
         assert_eq!(manager.todos.len(), 0);
     }
 
+    #[test]
+    fn test_handle_commands_undo_redo() {
+        let mut manager = create_test_manager();
+        manager
+            .add_todo("Test".to_string(), None, None, None, None)
+            .unwrap();
+        handle_commands(Commands::ClearList, &mut manager).unwrap();
+
+        handle_commands(Commands::Undo { steps: 1 }, &mut manager).unwrap();
+        assert_eq!(manager.todos.len(), 1);
+
+        handle_commands(Commands::Redo { steps: 1 }, &mut manager).unwrap();
+        assert_eq!(manager.todos.len(), 0);
+    }
+
     #[test]
     fn test_handle_commands_invalid_todo_id() {
         let mut manager = create_test_manager();
@@ -295,7 +674,7 @@ mod tests {
             TodoItem::new(3, "No date".to_string(), None, false, None, None, None),
         ];
 
-        apply_sorting(&mut todos, SortBy::Due, true).unwrap();
+        apply_sorting(&mut todos, &SortBy::Due, true, &Udas::new()).unwrap();
 
         assert_eq!(todos[0].id(), 2); // Early date first
         assert_eq!(todos[1].id(), 1); // Late date second
@@ -334,7 +713,7 @@ mod tests {
             ),
         ];
 
-        apply_sorting(&mut todos, SortBy::Priority, true).unwrap();
+        apply_sorting(&mut todos, &SortBy::Priority, true, &Udas::new()).unwrap();
 
         // Ascending: Low < Medium < High
         assert_eq!(todos[0].id(), 2); // Low
@@ -342,6 +721,44 @@ mod tests {
         assert_eq!(todos[2].id(), 1); // High
     }
 
+    #[test]
+    fn test_apply_sorting_by_uda() {
+        let udas: Udas =
+            toml::from_str("[size]\ntype = \"enum\"\nvalues = [\"S\", \"M\", \"L\"]").unwrap();
+        let mut todos: Vec<TodoItem> = [Some("L"), None, Some("S")]
+            .iter()
+            .enumerate()
+            .map(|(i, size)| {
+                let mut todo = TodoItem::new(
+                    i as u32 + 1,
+                    "Task".to_string(),
+                    None,
+                    false,
+                    None,
+                    None,
+                    None,
+                );
+                todo.set_uda("size", size.map(str::to_string));
+                todo
+            })
+            .collect();
+
+        let sort_by = SortBy::Uda("size".to_string());
+        apply_sorting(&mut todos, &sort_by, true, &udas).unwrap();
+        assert_eq!(
+            todos.iter().map(|t| t.id()).collect::<Vec<_>>(),
+            vec![3, 1, 2]
+        );
+        apply_sorting(&mut todos, &sort_by, false, &udas).unwrap();
+        assert_eq!(
+            todos.iter().map(|t| t.id()).collect::<Vec<_>>(),
+            vec![1, 3, 2]
+        );
+
+        let unknown = SortBy::Uda("points".to_string());
+        assert!(apply_sorting(&mut todos, &unknown, true, &udas).is_err());
+    }
+
     #[test]
     fn test_cmp_option_priority_ordering() {
         let high = Some(Priority::High);
@@ -357,10 +774,12 @@ mod tests {
 
     #[test]
     fn test_handle_list_command() {
-        let mut todos = vec![
+        let todos = vec![
             TodoItem::new(1, "Task 1".to_string(), None, true, None, None, None),
             TodoItem::new(2, "Task 2".to_string(), None, false, None, None, None),
         ];
+        let mut manager =
+            TodoManager::with_store(Box::new(InMemoryStore::with_todos(todos))).unwrap();
 
         let query = ListQuery {
             sort_by: SortBy::Due,
@@ -373,9 +792,10 @@ mod tests {
             due_today: false,
             due_tomorrow: false,
             due_within: None,
+            ..Default::default()
         };
 
-        let result = handle_list_command(&mut todos, query);
+        let result = handle_list_command(&mut manager, query, &[]);
         assert!(result.is_ok());
     }
 
@@ -388,8 +808,18 @@ mod tests {
             title: "Workflow test".to_string(),
             description: None,
             due_date: None,
+            scheduled: None,
+            wait: None,
             priority: Some("medium".to_string()),
+            estimate: None,
             tags: None,
+            project: Some("work".to_string()),
+            assignee: Some("alice".to_string()),
+            remind: vec![],
+            parent: None,
+            depends_on: None,
+            repeat: None,
+            set: vec![],
         };
         handle_commands(add_cmd, &mut manager).unwrap();
 
@@ -402,8 +832,18 @@ mod tests {
             title: None,
             description: None,
             due_date: None,
+            scheduled: None,
+            wait: None,
             priority: Some("high".to_string()),
+            estimate: None,
             tags: None,
+            parent: None,
+            depends_on: None,
+            repeat: None,
+            status: None,
+            assignee: None,
+            remind: vec![],
+            set: vec![],
         };
         handle_commands(edit_cmd, &mut manager).unwrap();
         assert_eq!(manager.todos[0].priority(), Some(Priority::High));
@@ -416,6 +856,6 @@ mod tests {
         // Delete
         let delete_cmd = Commands::Delete { id: todo_id };
         handle_commands(delete_cmd, &mut manager).unwrap();
-        assert_eq!(manager.todos.len(), 0);
+        assert!(manager.todos[0].is_trashed());
     }
 }
@@ -1,5 +1,5 @@
 pub mod commands;
 pub mod handlers;
 
-pub use commands::{Cli, Commands};
+pub use commands::{Cli, Commands, ReportCommands, TimerCommands, TrashCommands};
 pub use handlers::handle_commands;
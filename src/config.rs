@@ -0,0 +1,156 @@
+use crate::todo::{TodoError, TodoResult, UdaType, Udas};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+// Settings read from a TOML file, e.g.
+//
+//   user = "alice"
+//
+//   [uda.customer]
+//   type = "string"
+//
+//   [list]
+//   columns = ["customer"]
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    // Who "me" is for `list --mine` and `assign`; defaults to $USER
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub uda: Udas,
+    #[serde(default)]
+    pub list: ListConfig,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ListConfig {
+    // User-defined attributes shown as extra columns by `list`
+    #[serde(default)]
+    pub columns: Vec<String>,
+}
+
+impl Config {
+    // Load `path`, or the default location if none is given. A missing
+    // default file means an empty configuration.
+    pub fn load(path: Option<&str>) -> TodoResult<Self> {
+        let (path, required) = match path {
+            Some(path) => (PathBuf::from(path), true),
+            None => match default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Config::default()),
+            },
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(text) => Self::parse(&text).map_err(|reason| TodoError::InvalidConfig {
+                path: path.display().to_string(),
+                reason,
+            }),
+            Err(e) if !required && e.kind() == std::io::ErrorKind::NotFound => {
+                Ok(Config::default())
+            }
+            Err(e) => Err(TodoError::InvalidConfig {
+                path: path.display().to_string(),
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    // The configured user, else the login name from $USER
+    pub fn current_user(&self) -> Option<String> {
+        self.user
+            .clone()
+            .or_else(|| std::env::var("USER").ok())
+            .map(|user| user.trim().to_string())
+            .filter(|user| !user.is_empty())
+    }
+
+    fn parse(text: &str) -> Result<Self, String> {
+        let config: Config = toml::from_str(text).map_err(|e| e.message().to_string())?;
+        for (key, def) in &config.uda {
+            if def.kind == UdaType::Enum && def.values.is_empty() {
+                return Err(format!("enum attribute '{key}' needs a list of values"));
+            }
+        }
+        if let Some(column) = config
+            .list
+            .columns
+            .iter()
+            .find(|c| !config.uda.contains_key(*c))
+        {
+            return Err(format!("unknown column '{column}'"));
+        }
+        Ok(config)
+    }
+}
+
+// `$TODO_CONFIG`, else `todo/config.toml` in `$XDG_CONFIG_HOME` or `~/.config`
+fn default_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("TODO_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("todo").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_parse_udas() {
+        let config = Config::parse(
+            r#"
+            user = "alice"
+
+            [uda.size]
+            type = "enum"
+            values = ["S", "M", "L"]
+
+            [uda.points]
+            type = "number"
+            label = "Pts"
+
+            [list]
+            columns = ["points"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.uda["size"].values, vec!["S", "M", "L"]);
+        assert_eq!(config.uda["points"].label("points"), "Pts");
+        assert_eq!(config.list.columns, vec!["points"]);
+        assert_eq!(config.current_user(), Some("alice".to_string()));
+    }
+
+    #[test]
+    fn test_invalid_configs() {
+        for text in [
+            "[uda.size]\ntype = \"enum\"",
+            "[uda.size]\ntype = \"colour\"",
+            "[list]\ncolumns = [\"missing\"]",
+            "unknown = 1",
+        ] {
+            assert!(Config::parse(text).is_err(), "accepted: {text}");
+        }
+    }
+
+    #[test]
+    fn test_load() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "[uda.customer]\ntype = \"string\"").unwrap();
+        let config = Config::load(file.path().to_str()).unwrap();
+        assert!(config.uda.contains_key("customer"));
+
+        assert!(matches!(
+            Config::load(Some("/no/such/config.toml")),
+            Err(TodoError::InvalidConfig { .. })
+        ));
+    }
+}
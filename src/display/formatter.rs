@@ -1,10 +1,13 @@
+use crate::todo::{HistoryEntry, Status};
 use chrono::{Local, NaiveDateTime};
 
-pub fn format_status(status: bool) -> &'static str {
-    if status {
-        "✅"
-    } else {
-        "⬜"
+pub fn format_status(status: Status) -> &'static str {
+    match status {
+        Status::Pending => "⬜",
+        Status::InProgress => "🔄",
+        Status::Waiting => "⏳",
+        Status::Done => "✅",
+        Status::Cancelled => "❌",
     }
 }
 
@@ -50,6 +53,20 @@ pub fn truncate_text(text: &str, max_len: usize) -> String {
     }
 }
 
+pub fn format_history_entry(entry: &HistoryEntry) -> String {
+    let value = |v: &Option<String>| match v {
+        Some(v) => format!("\"{v}\""),
+        None => "-".to_string(),
+    };
+    format!(
+        "{}  {:<12} {} → {}",
+        entry.timestamp.format("%d-%m-%Y %H:%M"),
+        entry.field,
+        value(&entry.old),
+        value(&entry.new)
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -66,12 +83,19 @@ mod tests {
 
     #[test]
     fn test_format_status_completed() {
-        assert_eq!(format_status(true), "✅");
+        assert_eq!(format_status(Status::Done), "✅");
     }
 
     #[test]
     fn test_format_status_incomplete() {
-        assert_eq!(format_status(false), "⬜");
+        assert_eq!(format_status(Status::Pending), "⬜");
+    }
+
+    #[test]
+    fn test_format_status_other_states() {
+        assert_eq!(format_status(Status::InProgress), "🔄");
+        assert_eq!(format_status(Status::Waiting), "⏳");
+        assert_eq!(format_status(Status::Cancelled), "❌");
     }
 
     #[test]
@@ -113,8 +137,9 @@ mod tests {
 
     #[test]
     fn test_format_due_date_within_week() {
+        let mock_now = test_date(2025, 8, 7, 8, 0); // Mock current time
         let week_date = test_date(2025, 8, 12, 12, 0); // 5 days from now
-        let result = format_due_date(Some(week_date));
+        let result = format_due_date_with_now(Some(week_date), Some(mock_now));
 
         assert!(result.starts_with("🟢"));
         assert!(result.contains("12-08 12:00"));
@@ -123,8 +148,9 @@ mod tests {
 
     #[test]
     fn test_format_due_date_beyond_week() {
+        let mock_now = test_date(2025, 8, 7, 8, 0); // Mock current time
         let future_date = test_date(2025, 8, 17, 10, 0); // 10 days from now
-        let result = format_due_date(Some(future_date));
+        let result = format_due_date_with_now(Some(future_date), Some(mock_now));
 
         assert!(result.starts_with("⚪"));
         assert!(result.contains("17-08-2025"));
@@ -160,4 +186,18 @@ mod tests {
         assert_eq!(result, "He...");
         assert_eq!(result.len(), 5);
     }
+
+    #[test]
+    fn test_format_history_entry() {
+        let entry = HistoryEntry::new(
+            "title",
+            Some("Old".to_string()),
+            None,
+            test_date(2025, 3, 1, 9, 5),
+        );
+        assert_eq!(
+            format_history_entry(&entry),
+            "01-03-2025 09:05  title        \"Old\" → -"
+        );
+    }
 }
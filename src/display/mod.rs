@@ -1,4 +1,7 @@
 pub mod formatter;
 pub mod table;
 
-pub use table::display_todos;
+pub use table::{
+    display_history, display_projects, display_reminders, display_tags, display_time_report,
+    display_todo_details, display_todo_table, display_todo_tree, display_todos,
+};
@@ -1,42 +1,394 @@
-use crate::display::formatter::{format_due_date, format_status, truncate_text};
-use crate::todo::TodoItem;
+use crate::display::formatter::{
+    format_due_date, format_history_entry, format_status, truncate_text,
+};
+use crate::todo::{format_duration, ActiveTimer, ProjectCount, Reminder, TagCount, TodoItem};
+use chrono::{Local, NaiveDateTime};
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, Write};
+
+// Width of each user-defined attribute column
+const UDA_WIDTH: usize = 15;
 
 pub fn display_todos(todos: &[TodoItem]) {
+    display_todo_tree(todos, todos);
+}
+
+// Print `todos` with subtasks indented under their parents. Progress on a
+// parent is counted over `all`, so subtasks hidden by a filter still count.
+pub fn display_todo_tree(todos: &[TodoItem], all: &[TodoItem]) {
+    display_todo_table(todos, all, &[]);
+}
+
+// Like `display_todo_tree`, with an extra column for each user-defined
+// attribute in `columns`, given as (key, header) pairs
+pub fn display_todo_table(todos: &[TodoItem], all: &[TodoItem], columns: &[(&str, &str)]) {
+    let _ = write_todo_table(todos, all, columns, &mut io::stdout());
+}
+
+pub fn write_todo_tree<W: Write>(
+    todos: &[TodoItem],
+    all: &[TodoItem],
+    writer: &mut W,
+) -> io::Result<()> {
+    write_todo_table(todos, all, &[], writer)
+}
+
+pub fn write_todo_table<W: Write>(
+    todos: &[TodoItem],
+    all: &[TodoItem],
+    columns: &[(&str, &str)],
+    writer: &mut W,
+) -> io::Result<()> {
     if todos.is_empty() {
-        println!("📭 No todos found.");
-        return;
-    }
-
-    println!("{:<3} ✓  {:<35} {:<30} Priority", "ID", "Todo", "Due Date");
-    println!("{}", "─".repeat(85));
-
-    for item in todos {
-        let truncated_todo = truncate_text(item.title(), 35);
-        let due_date = format_due_date(item.due_date());
-        let priority = item
-            .priority()
-            .as_ref()
-            .map(|p| p.to_string())
-            .unwrap_or_else(|| "-".to_string());
-
-        println!(
-            "{:<3} {} {:<35} {:<30} {}",
-            item.id(),
-            format_status(item.completed()),
-            truncated_todo,
-            due_date,
-            priority.trim()
-        );
+        writeln!(writer, "📭 No todos found.")?;
+        return Ok(());
+    }
+
+    let mut header = format!(
+        "{:<3} ✓  {:<35} {:<30} {:<10} {:<10} 📎",
+        "ID", "Todo", "Due Date", "Owner", "Priority"
+    );
+    for (_, label) in columns {
+        header.push_str(&format!(" {:<UDA_WIDTH$}", truncate_text(label, UDA_WIDTH)));
+    }
+    writeln!(writer, "{}", header.trim_end())?;
+    writeln!(
+        writer,
+        "{}",
+        "─".repeat(101 + columns.len() * (UDA_WIDTH + 1))
+    )?;
+
+    // Subtasks whose parent is not listed are shown at the top level
+    let listed: HashSet<u32> = todos.iter().map(|t| t.id()).collect();
+    for item in todos
+        .iter()
+        .filter(|t| t.parent_id().iter().all(|p| !listed.contains(p)))
+    {
+        write_branch(item, 0, todos, all, columns, writer)?;
+    }
+    Ok(())
+}
+
+fn write_branch<W: Write>(
+    item: &TodoItem,
+    depth: usize,
+    todos: &[TodoItem],
+    all: &[TodoItem],
+    columns: &[(&str, &str)],
+    writer: &mut W,
+) -> io::Result<()> {
+    let indent = match depth {
+        0 => String::new(),
+        _ => format!("{}└ ", "  ".repeat(depth - 1)),
+    };
+    let progress = subtask_progress(item.id(), all)
+        .map(|(done, total)| format!(" [{done}/{total}]"))
+        .unwrap_or_default();
+    let width = 35usize.saturating_sub(indent.chars().count() + progress.chars().count());
+    let label = format!("{indent}{}{progress}", truncate_text(item.title(), width));
+    // Pending todos waiting on unfinished dependencies
+    let status = match item.is_blocked_by(all) {
+        true => "⛔",
+        false => format_status(item.status()),
+    };
+    let due_date = format_due_date(item.due_date());
+    let owner = truncate_text(item.assignee().unwrap_or("-"), 10);
+    let priority = item
+        .priority()
+        .as_ref()
+        .map(|p| p.to_string())
+        .unwrap_or_else(|| "-".to_string());
+
+    // Number of attached links and files, if any
+    let attachments = match item.attachments().len() {
+        0 => String::new(),
+        n => n.to_string(),
+    };
+    // Priorities start with a double-width emoji
+    let priority_width = if item.priority().is_some() { 9 } else { 10 };
+    let mut line = format!(
+        "{:<3} {} {:<35} {:<30} {:<10} {:<priority_width$} {:<2}",
+        item.id(),
+        status,
+        label,
+        due_date,
+        owner,
+        priority.trim(),
+        attachments
+    );
+    for (key, _) in columns {
+        let value = truncate_text(item.uda(key).unwrap_or("-"), UDA_WIDTH);
+        line.push_str(&format!(" {value:<UDA_WIDTH$}"));
+    }
+    writeln!(writer, "{}", line.trim_end())?;
+
+    for child in todos.iter().filter(|t| t.parent_id() == Some(item.id())) {
+        write_branch(child, depth + 1, todos, all, columns, writer)?;
+    }
+    Ok(())
+}
+
+// (closed, total) over the direct subtasks of `id`, if it has any. Cancelled
+// subtasks count as finished so a parent with only closed subtasks is full.
+fn subtask_progress(id: u32, all: &[TodoItem]) -> Option<(usize, usize)> {
+    let children: Vec<&TodoItem> = all
+        .iter()
+        .filter(|t| t.parent_id() == Some(id) && !t.is_trashed())
+        .collect();
+    if children.is_empty() {
+        return None;
+    }
+    let done = children.iter().filter(|t| t.is_closed()).count();
+    Some((done, children.len()))
+}
+
+pub fn display_reminders(reminders: &[(&TodoItem, Reminder)]) {
+    let _ = write_reminders(reminders, &mut io::stdout());
+}
+
+pub fn write_reminders<W: Write>(
+    reminders: &[(&TodoItem, Reminder)],
+    writer: &mut W,
+) -> io::Result<()> {
+    if reminders.is_empty() {
+        writeln!(writer, "🔕 No reminders.")?;
+        return Ok(());
+    }
+
+    for (todo, reminder) in reminders {
+        let due = todo
+            .due_date()
+            .map(|d| format!(" (due {})", d.format("%d-%m-%Y %H:%M")))
+            .unwrap_or_default();
+        writeln!(
+            writer,
+            "🔔 {}  {:<3} {}{due}",
+            reminder.at.format("%d-%m-%Y %H:%M"),
+            todo.id(),
+            todo.title()
+        )?;
+    }
+    Ok(())
+}
+
+pub fn display_tags(counts: &BTreeMap<String, TagCount>) {
+    let _ = write_tags(counts, &mut io::stdout());
+}
+
+pub fn write_tags<W: Write>(counts: &BTreeMap<String, TagCount>, writer: &mut W) -> io::Result<()> {
+    if counts.is_empty() {
+        writeln!(writer, "📭 No tags found.")?;
+        return Ok(());
+    }
+
+    writeln!(writer, "{:<29} {:>6} {:>6}", "🏷️ Tag", "Open", "Done")?;
+    writeln!(writer, "{}", "─".repeat(42))?;
+    for (tag, count) in counts {
+        writeln!(
+            writer,
+            "{:<29} {:>6} {:>6}",
+            truncate_text(tag, 29),
+            count.open,
+            count.done
+        )?;
+    }
+    Ok(())
+}
+
+pub fn display_projects(counts: &BTreeMap<String, ProjectCount>) {
+    let _ = write_projects(counts, &mut io::stdout());
+}
+
+// Sub-projects are indented below their parent, which sorts first
+pub fn write_projects<W: Write>(
+    counts: &BTreeMap<String, ProjectCount>,
+    writer: &mut W,
+) -> io::Result<()> {
+    if counts.is_empty() {
+        writeln!(writer, "📭 No projects found.")?;
+        return Ok(());
+    }
+
+    writeln!(writer, "{:<35} {:>6} {:>6}", "📁 Project", "Open", "Total")?;
+    writeln!(writer, "{}", "─".repeat(48))?;
+    // Compare segment by segment so "work.backend" stays under "work" even
+    // though "work-x" sorts between them as a plain string
+    let mut projects: Vec<(&String, &ProjectCount)> = counts.iter().collect();
+    projects.sort_by(|(a, _), (b, _)| a.split('.').cmp(b.split('.')));
+    for (project, count) in projects {
+        let (depth, name) = match project.rsplit_once('.') {
+            Some((_, name)) => (project.matches('.').count(), name),
+            None => (0, project.as_str()),
+        };
+        let label = format!("{}{}", "  ".repeat(depth), name);
+        writeln!(
+            writer,
+            "{:<36} {:>6} {:>6}",
+            truncate_text(&label, 36),
+            count.open,
+            count.total
+        )?;
+    }
+    Ok(())
+}
+
+pub fn display_history(todo: &TodoItem) {
+    println!("📜 History of todo {}: {}", todo.id(), todo.title());
+    println!("{}  created", todo.created_at().format("%d-%m-%Y %H:%M"));
+    for entry in todo.history() {
+        println!("{}", format_history_entry(entry));
+    }
+}
+
+pub fn display_todo_details(todo: &TodoItem, timer: Option<&ActiveTimer>) {
+    let date = |d: Option<NaiveDateTime>| {
+        d.map(|d| d.format("%d-%m-%Y %H:%M").to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let or_dash = |s: Option<String>| s.unwrap_or_else(|| "-".to_string());
+
+    println!(
+        "{} {}: {}",
+        format_status(todo.status()),
+        todo.id(),
+        todo.title()
+    );
+    if let Some(description) = todo.description() {
+        println!("   {description}");
+    }
+    println!("Status:     {}", todo.status());
+    println!(
+        "Priority:   {}",
+        or_dash(todo.priority().map(|p| p.to_string()))
+    );
+    println!("Tags:       {}", or_dash(todo.tags().map(|t| t.join(", "))));
+    println!("Project:    {}", todo.project().unwrap_or("-"));
+    println!("Assignee:   {}", todo.assignee().unwrap_or("-"));
+    println!("Due:        {}", date(todo.due_date()));
+    println!("Scheduled:  {}", date(todo.scheduled()));
+    println!("Wait until: {}", date(todo.wait_until()));
+    println!("Created:    {}", date(Some(todo.created_at())));
+    println!("Completed:  {}", date(todo.completed_at()));
+    println!(
+        "Estimate:   {}",
+        or_dash(todo.estimate().map(format_duration))
+    );
+    println!(
+        "Spent:      {} in {} session(s)",
+        format_duration(todo.time_spent(None)),
+        todo.time_log().len()
+    );
+    if let Some(timer) = timer.filter(|t| t.id == todo.id()) {
+        let running = Local::now().naive_local() - timer.started_at;
+        println!("⏱️ Timer running for {}", format_duration(running));
+    }
+    for (key, value) in todo.udas() {
+        println!("{:<11} {value}", format!("{key}:"));
+    }
+    if !todo.reminders().is_empty() {
+        println!("🔔 Reminders:");
+        for reminder in todo.reminders() {
+            let delivered = if reminder.delivered {
+                "  (delivered)"
+            } else {
+                ""
+            };
+            println!("  {}{delivered}", reminder.at.format("%d-%m-%Y %H:%M"));
+        }
+    }
+    if !todo.attachments().is_empty() {
+        println!("📎 Attachments:");
+        for attachment in todo.attachments() {
+            println!("  {}", attachment.target);
+        }
+    }
+    if !todo.notes().is_empty() {
+        println!("📝 Notes:");
+        for note in todo.notes() {
+            println!(
+                "{}  {}",
+                note.created_at.format("%d-%m-%Y %H:%M"),
+                note.text
+            );
+        }
+    }
+}
+
+// Time tracked per todo, counting only work at or after `since`
+pub fn display_time_report(
+    todos: &[TodoItem],
+    since: Option<NaiveDateTime>,
+    timer: Option<&ActiveTimer>,
+) {
+    let _ = write_time_report(todos, since, timer, &mut io::stdout());
+}
+
+pub fn write_time_report<W: Write>(
+    todos: &[TodoItem],
+    since: Option<NaiveDateTime>,
+    timer: Option<&ActiveTimer>,
+    writer: &mut W,
+) -> io::Result<()> {
+    match since {
+        Some(since) => writeln!(
+            writer,
+            "⏱️ Time tracked since {}",
+            since.format("%d-%m-%Y %H:%M")
+        )?,
+        None => writeln!(writer, "⏱️ Time tracked")?,
     }
+
+    let rows: Vec<(&TodoItem, chrono::Duration)> = todos
+        .iter()
+        .map(|t| (t, t.time_spent(since)))
+        .filter(|(_, spent)| *spent > chrono::Duration::zero())
+        .collect();
+    if rows.is_empty() {
+        writeln!(writer, "📭 No time tracked.")?;
+    } else {
+        writeln!(
+            writer,
+            "{:<3} {:<35} {:>10} {:>10}",
+            "ID", "Todo", "Spent", "Estimate"
+        )?;
+        writeln!(writer, "{}", "─".repeat(61))?;
+        for (todo, spent) in &rows {
+            let estimate = todo
+                .estimate()
+                .map(format_duration)
+                .unwrap_or_else(|| "-".to_string());
+            writeln!(
+                writer,
+                "{:<3} {:<35} {:>10} {:>10}",
+                todo.id(),
+                truncate_text(todo.title(), 35),
+                format_duration(*spent),
+                estimate
+            )?;
+        }
+        let total = rows
+            .iter()
+            .fold(chrono::Duration::zero(), |total, (_, spent)| total + *spent);
+        writeln!(writer, "{}", "─".repeat(61))?;
+        writeln!(writer, "{:<39} {:>10}", "Total", format_duration(total))?;
+    }
+
+    if let Some(timer) = timer {
+        writeln!(
+            writer,
+            "⏱️ Timer still running on todo {} (not included)",
+            timer.id
+        )?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::todo::Priority;
     use crate::todo::TodoItem;
+    use crate::todo::{Priority, Status};
     use chrono::NaiveDateTime;
-    use std::io::{self, Write};
 
     pub fn display_todos_to_writer<W: Write>(todos: &[TodoItem], writer: &mut W) -> io::Result<()> {
         if todos.is_empty() {
@@ -63,7 +415,7 @@ mod tests {
                 writer,
                 "{:<3} {} {:<35} {:<30} {}",
                 item.id(),
-                format_status(item.completed()),
+                format_status(item.status()),
                 truncated_todo,
                 due_date,
                 priority.trim()
@@ -250,4 +602,241 @@ mod tests {
         assert!(todo_line.len() >= 60);
         assert!(todo_line.starts_with("999"));
     }
+
+    #[test]
+    fn test_write_todo_tree_nests_subtasks() {
+        let epic = create_test_todo(1, "Epic", false, None, None);
+        let task = create_test_todo(2, "Task", true, None, None).with_parent(Some(1));
+        let step = create_test_todo(3, "Step", false, None, None).with_parent(Some(2));
+        let other = create_test_todo(4, "Other", false, None, None).with_parent(Some(1));
+        let todos = vec![epic, task, step, other];
+
+        let mut output = Vec::new();
+        write_todo_tree(&todos, &todos, &mut output).unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output_str.lines().collect();
+
+        assert_eq!(lines.len(), 6);
+        assert!(lines[2].contains("Epic [1/2]"));
+        assert!(lines[3].starts_with("2  ") && lines[3].contains("└ Task [0/1]"));
+        assert!(lines[4].starts_with("3  ") && lines[4].contains("  └ Step"));
+        assert!(lines[5].starts_with("4  ") && lines[5].contains("└ Other"));
+    }
+
+    #[test]
+    fn test_write_todo_tree_counts_hidden_subtasks() {
+        let all = vec![
+            create_test_todo(1, "Epic", false, None, None),
+            create_test_todo(2, "Done", true, None, None).with_parent(Some(1)),
+            create_test_todo(3, "Open", false, None, None).with_parent(Some(1)),
+        ];
+        let pending: Vec<TodoItem> = all.iter().filter(|t| !t.completed()).cloned().collect();
+
+        let mut output = Vec::new();
+        write_todo_tree(&pending, &all, &mut output).unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+
+        assert!(output_str.contains("Epic [1/2]"));
+        assert!(!output_str.contains("Done"));
+    }
+
+    #[test]
+    fn test_write_todo_tree_counts_cancelled_as_finished() {
+        let mut dropped = create_test_todo(3, "Dropped", false, None, None).with_parent(Some(1));
+        dropped.set_status(Status::Cancelled);
+        let todos = vec![
+            create_test_todo(1, "Epic", false, None, None),
+            create_test_todo(2, "Done", true, None, None).with_parent(Some(1)),
+            dropped,
+        ];
+
+        let mut output = Vec::new();
+        write_todo_tree(&todos, &todos, &mut output).unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+
+        assert!(output_str.contains("Epic [2/2]"));
+    }
+
+    #[test]
+    fn test_write_todo_tree_marks_blocked() {
+        let design = create_test_todo(1, "Design", false, None, None);
+        let build = create_test_todo(2, "Build", false, None, None).with_dependencies(vec![1]);
+        let todos = vec![design, build];
+
+        let mut output = Vec::new();
+        write_todo_tree(&todos, &todos, &mut output).unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output_str.lines().collect();
+
+        assert!(lines[2].contains("⬜ Design"));
+        assert!(lines[3].contains("⛔ Build"));
+    }
+
+    #[test]
+    fn test_write_todo_tree_counts_attachments() {
+        let now = chrono::Local::now().naive_local();
+        let mut linked = create_test_todo(1, "Linked", false, None, Some(Priority::Low));
+        for url in ["https://example.com/1", "https://example.com/2"] {
+            linked.attach(crate::todo::Attachment::parse(url, now).unwrap());
+        }
+        let plain = create_test_todo(2, "Plain", false, None, Some(Priority::Low));
+        let todos = vec![linked, plain];
+
+        let mut output = Vec::new();
+        write_todo_tree(&todos, &todos, &mut output).unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output_str.lines().collect();
+
+        assert!(lines[0].ends_with("Priority   📎"));
+        assert!(lines[2].ends_with("Low     2"));
+        assert!(lines[3].ends_with("Low"));
+    }
+
+    #[test]
+    fn test_write_todo_table_uda_columns() {
+        let mut story = create_test_todo(1, "Story", false, None, None);
+        story.set_uda("points", Some("5".to_string()));
+        let chore = create_test_todo(2, "Chore", false, None, None);
+        let todos = vec![story, chore];
+
+        let mut output = Vec::new();
+        write_todo_table(&todos, &todos, &[("points", "Pts")], &mut output).unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output_str.lines().collect();
+
+        assert!(lines[0].ends_with("📎 Pts"));
+        assert!(lines[2].ends_with("   5"));
+        assert!(lines[3].ends_with("   -"));
+    }
+
+    #[test]
+    fn test_write_todo_tree_shows_owner() {
+        let todos = vec![
+            create_test_todo(1, "Review", false, None, None)
+                .with_assignee(Some("alice".to_string())),
+            create_test_todo(2, "Deploy", false, None, None),
+        ];
+
+        let mut output = Vec::new();
+        write_todo_tree(&todos, &todos, &mut output).unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output_str.lines().collect();
+
+        assert!(lines[0].contains("Due Date                       Owner      Priority"));
+        assert!(lines[2].ends_with("alice      -"));
+        assert!(lines[3].ends_with("-          -"));
+    }
+
+    #[test]
+    fn test_write_reminders() {
+        let due = test_date(2030, 3, 5, 12, 0);
+        let todo = create_test_todo(3, "Dentist", false, Some(due), None);
+        let reminder = Reminder::parse("1h before", Some(due)).unwrap();
+
+        let mut output = Vec::new();
+        write_reminders(&[(&todo, reminder)], &mut output).unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+
+        assert_eq!(
+            output_str.trim_end(),
+            "🔔 05-03-2030 11:00  3   Dentist (due 05-03-2030 12:00)"
+        );
+
+        let mut output = Vec::new();
+        write_reminders(&[], &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "🔕 No reminders.\n");
+    }
+
+    #[test]
+    fn test_write_tags() {
+        let tagged = |id, completed| {
+            let tags = Some(vec!["work".to_string()]);
+            TodoItem::new(id, "Task".to_string(), None, completed, None, None, tags)
+        };
+        let todos = vec![tagged(1, false), tagged(2, true)];
+
+        let mut output = Vec::new();
+        write_tags(&crate::todo::tag_counts(&todos), &mut output).unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output_str.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[2].starts_with("work ") && lines[2].ends_with("1      1"));
+    }
+
+    #[test]
+    fn test_write_projects() {
+        let todos = vec![
+            create_test_todo(1, "Api", true, None, None)
+                .with_project(Some("work.backend".to_string())),
+            create_test_todo(2, "Ui", false, None, None).with_project(Some("work".to_string())),
+        ];
+
+        let mut output = Vec::new();
+        write_projects(&crate::todo::project_counts(&todos), &mut output).unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output_str.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[2].starts_with("work ") && lines[2].ends_with("1      2"));
+        assert!(lines[3].starts_with("  backend ") && lines[3].ends_with("0      1"));
+    }
+
+    #[test]
+    fn test_write_projects_keeps_subprojects_under_parent() {
+        let todos: Vec<TodoItem> = ["work.backend", "work-x", "work"]
+            .iter()
+            .enumerate()
+            .map(|(i, project)| {
+                create_test_todo(i as u32 + 1, "Task", false, None, None)
+                    .with_project(Some(project.to_string()))
+            })
+            .collect();
+
+        let mut output = Vec::new();
+        write_projects(&crate::todo::project_counts(&todos), &mut output).unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        let names: Vec<&str> = output_str
+            .lines()
+            .skip(2)
+            .map(|line| line.split_whitespace().next().unwrap())
+            .collect();
+
+        assert_eq!(names, vec!["work", "backend", "work-x"]);
+    }
+
+    #[test]
+    fn test_write_time_report() {
+        let start = NaiveDateTime::parse_from_str("2025-03-05 09:00", "%Y-%m-%d %H:%M").unwrap();
+        let hour = chrono::Duration::try_hours(1).unwrap();
+        let mut billed =
+            create_test_todo(1, "Billed", false, None, None).with_estimate(Some(hour * 2));
+        billed.log_time(crate::todo::TimeEntry::new(start, start + hour));
+        billed.log_time(crate::todo::TimeEntry::new(
+            start + hour * 48,
+            start + hour * 50,
+        ));
+        let idle = create_test_todo(2, "Idle", false, None, None);
+        let timer = ActiveTimer {
+            id: 2,
+            started_at: start,
+        };
+
+        let mut output = Vec::new();
+        write_time_report(
+            &[billed, idle],
+            Some(start + hour * 24),
+            Some(&timer),
+            &mut output,
+        )
+        .unwrap();
+        let output_str = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output_str.lines().collect();
+
+        assert!(lines[0].contains("06-03-2025 09:00"));
+        assert!(lines[3].starts_with("1  ") && lines[3].contains("2h 00m"));
+        assert!(!output_str.contains("Idle"));
+        assert!(lines[5].starts_with("Total") && lines[5].ends_with("2h 00m"));
+        assert!(lines[6].contains("todo 2"));
+    }
 }
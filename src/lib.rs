@@ -1,8 +1,11 @@
 pub mod cli;
+pub mod config;
 pub mod display;
+pub mod storage;
 pub mod todo;
 pub mod utils;
 
 pub use cli::Cli;
 pub use display::display_todos;
+pub use storage::TodoStore;
 pub use todo::{TodoItem, TodoManager};
@@ -1,14 +1,31 @@
 use clap::Parser;
+use std::str::FromStr;
 use todo_app::cli::{handle_commands, Cli};
+use todo_app::config::Config;
+use todo_app::storage::{archive_path, open_store, Backend};
 use todo_app::todo::TodoResult;
 use todo_app::TodoManager;
 
-fn main() -> TodoResult<()> {
+fn main() {
+    // Errors carry user-facing messages, so print them as such
+    if let Err(e) = run() {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
+
+fn run() -> TodoResult<()> {
     let cli = Cli::parse();
-    let mut manager = TodoManager::new(cli.file)?;
+    let backend = cli.backend.as_deref().map(Backend::from_str).transpose()?;
+    let config = Config::load(cli.config.as_deref())?;
+    let mut manager =
+        TodoManager::with_store(open_store(cli.file.clone(), backend)?)?.with_config(config);
+    if cli.command.uses_archive() {
+        let archive = open_store(archive_path(&cli.file), backend)?;
+        manager = manager.with_archive(archive)?;
+    }
 
     handle_commands(cli.command, &mut manager)?;
 
-    manager.save()?;
     Ok(())
 }
@@ -0,0 +1,548 @@
+use crate::storage::migrate::{migrate, CURRENT_VERSION};
+use crate::storage::TodoStore;
+use crate::todo::{ListQuery, TodoError, TodoItem, TodoResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// On-disk layout, see `migrate` for how older files are upgraded
+#[derive(Debug, Deserialize)]
+pub struct TodoFile {
+    pub version: u32,
+    pub next_id: u32,
+    pub todos: Vec<TodoItem>,
+    #[serde(default)]
+    pub meta: BTreeMap<String, Value>,
+    // Set when the file on disk used an older layout
+    #[serde(skip)]
+    pub upgraded: bool,
+}
+
+#[derive(Serialize)]
+struct TodoFileRef<'a> {
+    version: u32,
+    next_id: u32,
+    todos: &'a [TodoItem],
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    meta: &'a BTreeMap<String, Value>,
+}
+
+impl Default for TodoFile {
+    fn default() -> Self {
+        TodoFile {
+            version: CURRENT_VERSION,
+            next_id: 1,
+            todos: Vec::new(),
+            meta: BTreeMap::new(),
+            upgraded: false,
+        }
+    }
+}
+
+// Stores the whole collection as a versioned JSON document in a single file.
+// The file is rewritten on every change; reads are served from the in-memory
+// copy. Writes go through a temp file and a rename so the live file is never
+// left half written, and the previous version is kept next to it as `.bak`.
+// Between `begin` and `commit` changes are only made in memory and written once.
+pub struct JsonFileStore {
+    file_path: String,
+    todos: Vec<TodoItem>,
+    next_id: u32,
+    meta: BTreeMap<String, Value>,
+    batching: bool,
+    // Changes made while batching that are not written yet
+    dirty: bool,
+}
+
+impl JsonFileStore {
+    pub fn new(file_path: String) -> Self {
+        JsonFileStore {
+            file_path,
+            todos: Vec::new(),
+            next_id: 1,
+            meta: BTreeMap::new(),
+            batching: false,
+            dirty: false,
+        }
+    }
+
+    pub fn file_path(&self) -> &str {
+        &self.file_path
+    }
+
+    pub fn backup_path(file_path: &str) -> String {
+        format!("{file_path}.bak")
+    }
+
+    pub fn load_todos(file_path: &str) -> TodoResult<Vec<TodoItem>> {
+        Self::load_file(file_path).map(|file| file.todos)
+    }
+
+    // Load the file, falling back to the backup when the main file is
+    // corrupt or was truncated by an interrupted write
+    pub fn load_file(file_path: &str) -> TodoResult<TodoFile> {
+        let result = Self::read_file(file_path);
+
+        let damaged = Path::new(file_path).exists()
+            && matches!(result, Ok(None) | Err(TodoError::SerializationError(_)));
+        let backup_path = Self::backup_path(file_path);
+        if damaged && Path::new(&backup_path).exists() {
+            if let Ok(Some(file)) = Self::read_file(&backup_path) {
+                eprintln!(
+                    "⚠️ Could not read '{file_path}', recovered {} todo(s) from '{backup_path}'",
+                    file.todos.len()
+                );
+                return Ok(file);
+            }
+        }
+
+        result.map(Option::unwrap_or_default)
+    }
+
+    // `None` means the file is missing or empty
+    fn read_file(file_path: &str) -> TodoResult<Option<TodoFile>> {
+        if !Path::new(file_path).exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(file_path).map_err(|e| TodoError::FileError {
+            operation: "read".to_string(),
+            path: file_path.to_string(),
+            source: e,
+        })?;
+
+        if content.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let document: serde_json::Value = serde_json::from_str(&content)?;
+        let upgraded =
+            document.get("version").and_then(|v| v.as_u64()) != Some(u64::from(CURRENT_VERSION));
+        let mut file: TodoFile = serde_json::from_value(migrate(document)?)?;
+        file.upgraded = upgraded;
+        Ok(Some(file))
+    }
+
+    fn write(&mut self) -> TodoResult<()> {
+        if self.batching {
+            self.dirty = true;
+            return Ok(());
+        }
+        let content = serde_json::to_string(&TodoFileRef {
+            version: CURRENT_VERSION,
+            next_id: self.next_id,
+            todos: &self.todos,
+            meta: &self.meta,
+        })?;
+        write_atomic(&self.file_path, content.as_bytes())
+    }
+
+    // Never hand out an id at or below one that is already in use
+    fn bump_next_id(&mut self) {
+        let max_id = self.todos.iter().map(|t| t.id()).max().unwrap_or(0);
+        self.next_id = self.next_id.max(max_id + 1);
+    }
+}
+
+fn write_atomic(file_path: &str, content: &[u8]) -> TodoResult<()> {
+    let file_error = |operation: &str, path: &str| {
+        let operation = operation.to_string();
+        let path = path.to_string();
+        move |source| TodoError::FileError {
+            operation,
+            path,
+            source,
+        }
+    };
+
+    // Unique per process and per write, so a concurrent writer or a file
+    // left behind by a crash never gets reused
+    static WRITE_COUNT: AtomicU32 = AtomicU32::new(0);
+    let tmp_path = format!(
+        "{file_path}.{}.{}.tmp",
+        std::process::id(),
+        WRITE_COUNT.fetch_add(1, Ordering::Relaxed)
+    );
+    let replace = || -> TodoResult<()> {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&tmp_path)
+            .map_err(file_error("create", &tmp_path))?;
+        file.write_all(content)
+            .map_err(file_error("write", &tmp_path))?;
+        file.sync_all().map_err(file_error("sync", &tmp_path))?;
+
+        if Path::new(file_path).exists() {
+            let backup_path = JsonFileStore::backup_path(file_path);
+            fs::copy(file_path, &backup_path).map_err(file_error("back up", file_path))?;
+        }
+        fs::rename(&tmp_path, file_path).map_err(file_error("replace", file_path))
+    };
+    if let Err(e) = replace() {
+        fs::remove_file(&tmp_path).ok();
+        return Err(e);
+    }
+
+    // Make the rename itself durable
+    #[cfg(unix)]
+    if let Some(dir) = Path::new(file_path).parent() {
+        let dir = if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        };
+        if let Ok(dir) = File::open(dir) {
+            dir.sync_all().ok();
+        }
+    }
+
+    Ok(())
+}
+
+impl TodoStore for JsonFileStore {
+    fn location(&self) -> &str {
+        &self.file_path
+    }
+
+    fn lock_path(&self) -> Option<String> {
+        Some(format!("{}.lock", self.file_path))
+    }
+
+    fn next_id(&self) -> u32 {
+        self.next_id
+    }
+
+    fn load(&mut self) -> TodoResult<Vec<TodoItem>> {
+        let file = Self::load_file(&self.file_path)?;
+        self.todos = file.todos;
+        self.next_id = file.next_id;
+        self.meta = file.meta;
+        self.bump_next_id();
+        // Persist upgrades right away so values filled in by migrations
+        // (such as uuids) stay stable across runs
+        if file.upgraded {
+            self.write()?;
+        }
+        Ok(self.todos.clone())
+    }
+
+    fn save(&mut self, todos: &[TodoItem]) -> TodoResult<()> {
+        self.todos = todos.to_vec();
+        self.bump_next_id();
+        self.write()
+    }
+
+    fn insert(&mut self, todo: &TodoItem) -> TodoResult<()> {
+        self.todos.push(todo.clone());
+        self.bump_next_id();
+        self.write()
+    }
+
+    fn update(&mut self, todo: &TodoItem) -> TodoResult<()> {
+        let id = todo.id();
+        let existing = self
+            .todos
+            .iter_mut()
+            .find(|t| t.id() == id)
+            .ok_or(TodoError::TodoNotFound { id })?;
+        *existing = todo.clone();
+        self.write()
+    }
+
+    fn delete(&mut self, id: u32) -> TodoResult<()> {
+        let original_len = self.todos.len();
+        self.todos.retain(|t| t.id() != id);
+        if self.todos.len() == original_len {
+            return Err(TodoError::TodoNotFound { id });
+        }
+        self.write()
+    }
+
+    fn query(&mut self, query: &ListQuery) -> TodoResult<Vec<TodoItem>> {
+        Ok(self
+            .todos
+            .iter()
+            .filter(|t| query.item_passes_filters(t))
+            .cloned()
+            .collect())
+    }
+
+    fn meta(&self, key: &str) -> TodoResult<Option<Value>> {
+        Ok(self.meta.get(key).cloned())
+    }
+
+    fn begin(&mut self) -> TodoResult<()> {
+        self.batching = true;
+        Ok(())
+    }
+
+    fn commit(&mut self) -> TodoResult<()> {
+        self.batching = false;
+        if std::mem::take(&mut self.dirty) {
+            self.write()?;
+        }
+        Ok(())
+    }
+
+    // Forget the unwritten changes by reading the file again
+    fn rollback(&mut self) -> TodoResult<()> {
+        self.batching = false;
+        if std::mem::take(&mut self.dirty) {
+            self.load()?;
+        }
+        Ok(())
+    }
+
+    fn set_meta(&mut self, key: &str, value: Value) -> TodoResult<()> {
+        self.meta.insert(key.to_string(), value);
+        self.write()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::{NamedTempFile, TempDir};
+
+    fn create_test_store() -> (NamedTempFile, JsonFileStore) {
+        let temp_file = NamedTempFile::new().expect("Failed to create temp file");
+        let store = JsonFileStore::new(temp_file.path().to_string_lossy().into_owned());
+        (temp_file, store)
+    }
+
+    fn todo(id: u32, title: &str) -> TodoItem {
+        TodoItem::new(id, title.to_string(), None, false, None, None, None)
+    }
+
+    #[test]
+    fn test_load_todos_missing_file() {
+        let (temp_file, _) = create_test_store();
+        let file_path = temp_file.path().to_string_lossy().into_owned();
+        fs::remove_file(&file_path).ok();
+
+        let todos = JsonFileStore::load_todos(&file_path).unwrap();
+        assert!(todos.is_empty());
+    }
+
+    #[test]
+    fn test_load_todos_empty_file() {
+        let (temp_file, _) = create_test_store();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        fs::write(file_path, "").unwrap();
+
+        let todos = JsonFileStore::load_todos(file_path).unwrap();
+        assert_eq!(todos.len(), 0);
+    }
+
+    #[test]
+    fn test_load_todos_invalid_json() {
+        let (temp_file, mut store) = create_test_store();
+        fs::write(temp_file.path(), "{not json").unwrap();
+
+        assert!(matches!(
+            store.load(),
+            Err(TodoError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_changes_are_written_to_disk() {
+        let (temp_file, mut store) = create_test_store();
+        let file_path = temp_file.path().to_str().unwrap();
+
+        store.insert(&todo(1, "First")).unwrap();
+        store.insert(&todo(2, "Second")).unwrap();
+        store.update(&todo(1, "First (edited)")).unwrap();
+        store.delete(2).unwrap();
+
+        let on_disk = JsonFileStore::load_todos(file_path).unwrap();
+        assert_eq!(on_disk.len(), 1);
+        assert_eq!(on_disk[0].title(), "First (edited)");
+    }
+
+    #[test]
+    fn test_save_replaces_collection() {
+        let (temp_file, mut store) = create_test_store();
+
+        store.insert(&todo(1, "Old")).unwrap();
+        store.save(&[todo(5, "New")]).unwrap();
+
+        let mut reopened = JsonFileStore::new(temp_file.path().to_string_lossy().into_owned());
+        let todos = reopened.load().unwrap();
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].id(), 5);
+    }
+
+    #[test]
+    fn test_delete_missing_todo() {
+        let (_temp_file, mut store) = create_test_store();
+
+        assert!(matches!(
+            store.delete(42),
+            Err(TodoError::TodoNotFound { id: 42 })
+        ));
+    }
+
+    #[test]
+    fn test_write_keeps_backup_and_no_temp_file() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("todos.json").to_string_lossy().into_owned();
+        let mut store = JsonFileStore::new(file_path.clone());
+
+        store.insert(&todo(1, "First")).unwrap();
+        assert!(!Path::new(&JsonFileStore::backup_path(&file_path)).exists());
+
+        store.insert(&todo(2, "Second")).unwrap();
+
+        let backup = JsonFileStore::load_todos(&JsonFileStore::backup_path(&file_path)).unwrap();
+        assert_eq!(backup.len(), 1);
+        assert_eq!(JsonFileStore::load_todos(&file_path).unwrap().len(), 2);
+        assert_eq!(temp_files(&dir), 0);
+    }
+
+    fn temp_files(dir: &TempDir) -> usize {
+        fs::read_dir(dir.path())
+            .unwrap()
+            .filter(|e| {
+                e.as_ref()
+                    .unwrap()
+                    .path()
+                    .to_string_lossy()
+                    .ends_with(".tmp")
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_failed_write_removes_temp_file() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("todos.json");
+        // A directory in place of the file makes the backup and rename fail
+        fs::create_dir(&file_path).unwrap();
+        let mut store = JsonFileStore::new(file_path.to_string_lossy().into_owned());
+
+        assert!(matches!(
+            store.insert(&todo(1, "First")),
+            Err(TodoError::FileError { .. })
+        ));
+        assert_eq!(temp_files(&dir), 0);
+    }
+
+    #[test]
+    fn test_load_recovers_from_backup_when_corrupt() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("todos.json").to_string_lossy().into_owned();
+        let mut store = JsonFileStore::new(file_path.clone());
+        store.insert(&todo(1, "Saved")).unwrap();
+        store.insert(&todo(2, "Lost")).unwrap();
+
+        fs::write(&file_path, "{\"version\": 1, \"todos\": [{\"id\": 1, \"tit").unwrap();
+
+        let todos = JsonFileStore::load_todos(&file_path).unwrap();
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].title(), "Saved");
+    }
+
+    #[test]
+    fn test_load_recovers_from_backup_when_truncated() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("todos.json").to_string_lossy().into_owned();
+        let mut store = JsonFileStore::new(file_path.clone());
+        store.insert(&todo(1, "Saved")).unwrap();
+        store.insert(&todo(2, "Also saved")).unwrap();
+
+        fs::write(&file_path, "").unwrap();
+
+        assert_eq!(JsonFileStore::load_todos(&file_path).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_load_fails_when_backup_also_corrupt() {
+        let dir = TempDir::new().unwrap();
+        let file_path = dir.path().join("todos.json").to_string_lossy().into_owned();
+        fs::write(&file_path, "not json").unwrap();
+        fs::write(JsonFileStore::backup_path(&file_path), "also not json").unwrap();
+
+        assert!(matches!(
+            JsonFileStore::load_todos(&file_path),
+            Err(TodoError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_writes_versioned_envelope() {
+        let (temp_file, mut store) = create_test_store();
+
+        store.insert(&todo(1, "First")).unwrap();
+        store.insert(&todo(2, "Second")).unwrap();
+        store.delete(2).unwrap();
+
+        let content = fs::read_to_string(temp_file.path()).unwrap();
+        let document: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(document["version"], CURRENT_VERSION);
+        assert_eq!(document["next_id"], 3);
+        assert_eq!(document["todos"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_loads_legacy_bare_array() {
+        let (temp_file, mut store) = create_test_store();
+        fs::write(
+            temp_file.path(),
+            r#"[{"id": 4, "title": "Legacy", "description": null, "completed": false,
+                "due_date": null, "priority": null, "tags": null,
+                "created_at": "2024-01-01T10:00:00", "updated_at": "2024-01-01T10:00:00"}]"#,
+        )
+        .unwrap();
+
+        let todos = store.load().unwrap();
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].title(), "Legacy");
+        assert_eq!(store.next_id(), 5);
+
+        // The file is upgraded in place, keeping the generated uuid
+        let content = fs::read_to_string(temp_file.path()).unwrap();
+        assert!(content.starts_with(&format!("{{\"version\":{CURRENT_VERSION}")));
+        let reloaded = JsonFileStore::load_todos(temp_file.path().to_str().unwrap()).unwrap();
+        assert_eq!(reloaded[0].uuid(), todos[0].uuid());
+    }
+
+    #[test]
+    fn test_refuses_newer_version() {
+        let (temp_file, mut store) = create_test_store();
+        let newer = CURRENT_VERSION + 1;
+        fs::write(
+            temp_file.path(),
+            format!(r#"{{"version": {newer}, "next_id": 1, "todos": []}}"#),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            store.load(),
+            Err(TodoError::UnsupportedVersion { found, .. }) if found == newer
+        ));
+    }
+
+    #[test]
+    fn test_meta_is_persisted() {
+        let (temp_file, mut store) = create_test_store();
+        assert_eq!(store.meta("journal").unwrap(), None);
+
+        store
+            .set_meta("journal", serde_json::json!({"undo": []}))
+            .unwrap();
+
+        let mut reopened = JsonFileStore::new(temp_file.path().to_string_lossy().into_owned());
+        reopened.load().unwrap();
+        assert_eq!(
+            reopened.meta("journal").unwrap(),
+            Some(serde_json::json!({"undo": []}))
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use crate::todo::{TodoError, TodoResult};
+use fs4::fs_std::FileExt;
+use std::fs::{File, OpenOptions};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+// Advisory exclusive lock on a sibling `.lock` file. Held for as long as the
+// value lives and released on drop, so other `todo` processes pointed at the
+// same store wait instead of overwriting each other's changes.
+#[derive(Debug)]
+pub struct FileLock {
+    file: File,
+    path: String,
+}
+
+impl FileLock {
+    pub fn acquire(path: &str, timeout: Duration) -> TodoResult<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)
+            .map_err(|e| TodoError::FileError {
+                operation: "open lock".to_string(),
+                path: path.to_string(),
+                source: e,
+            })?;
+
+        let started = Instant::now();
+        loop {
+            match FileExt::try_lock_exclusive(&file) {
+                Ok(true) => {
+                    return Ok(FileLock {
+                        file,
+                        path: path.to_string(),
+                    })
+                }
+                Ok(false) if started.elapsed() < timeout => thread::sleep(RETRY_INTERVAL),
+                Ok(false) => {
+                    return Err(TodoError::LockTimeout {
+                        path: path.to_string(),
+                        seconds: timeout.as_secs_f64(),
+                    })
+                }
+                Err(e) => {
+                    return Err(TodoError::FileError {
+                        operation: "lock".to_string(),
+                        path: path.to_string(),
+                        source: e,
+                    })
+                }
+            }
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        FileExt::unlock(&self.file).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_second_lock_times_out() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("todos.json.lock");
+        let path = path.to_str().unwrap();
+
+        let _held = FileLock::acquire(path, Duration::ZERO).unwrap();
+        let result = FileLock::acquire(path, Duration::from_millis(100));
+
+        match result {
+            Err(TodoError::LockTimeout { path: locked, .. }) => assert_eq!(locked, path),
+            other => panic!("Expected LockTimeout, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_lock_released_on_drop() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("todos.json.lock");
+        let path = path.to_str().unwrap();
+
+        let held = FileLock::acquire(path, Duration::ZERO).unwrap();
+        assert_eq!(held.path(), path);
+        drop(held);
+
+        assert!(FileLock::acquire(path, Duration::ZERO).is_ok());
+    }
+
+    #[test]
+    fn test_waits_for_lock_to_be_released() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("todos.json.lock");
+        let path = path.to_str().unwrap().to_string();
+
+        let held = FileLock::acquire(&path, Duration::ZERO).unwrap();
+        let releaser = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            drop(held);
+        });
+
+        assert!(FileLock::acquire(&path, Duration::from_secs(5)).is_ok());
+        releaser.join().unwrap();
+    }
+}
@@ -0,0 +1,175 @@
+use crate::storage::TodoStore;
+use crate::todo::{ListQuery, TodoError, TodoItem, TodoResult};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+#[derive(Debug)]
+pub struct InMemoryStore {
+    todos: Vec<TodoItem>,
+    next_id: u32,
+    meta: BTreeMap<String, Value>,
+}
+
+impl Default for InMemoryStore {
+    fn default() -> Self {
+        InMemoryStore {
+            todos: Vec::new(),
+            next_id: 1,
+            meta: BTreeMap::new(),
+        }
+    }
+}
+
+impl InMemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_todos(todos: Vec<TodoItem>) -> Self {
+        let mut store = InMemoryStore {
+            todos,
+            ..Self::default()
+        };
+        store.bump_next_id();
+        store
+    }
+
+    fn bump_next_id(&mut self) {
+        let max_id = self.todos.iter().map(|t| t.id()).max().unwrap_or(0);
+        self.next_id = self.next_id.max(max_id + 1);
+    }
+
+    pub fn todos(&self) -> &[TodoItem] {
+        &self.todos
+    }
+}
+
+impl TodoStore for InMemoryStore {
+    fn location(&self) -> &str {
+        ":memory:"
+    }
+
+    fn next_id(&self) -> u32 {
+        self.next_id
+    }
+
+    fn load(&mut self) -> TodoResult<Vec<TodoItem>> {
+        Ok(self.todos.clone())
+    }
+
+    fn save(&mut self, todos: &[TodoItem]) -> TodoResult<()> {
+        self.todos = todos.to_vec();
+        self.bump_next_id();
+        Ok(())
+    }
+
+    fn insert(&mut self, todo: &TodoItem) -> TodoResult<()> {
+        self.todos.push(todo.clone());
+        self.bump_next_id();
+        Ok(())
+    }
+
+    fn update(&mut self, todo: &TodoItem) -> TodoResult<()> {
+        let id = todo.id();
+        let existing = self
+            .todos
+            .iter_mut()
+            .find(|t| t.id() == id)
+            .ok_or(TodoError::TodoNotFound { id })?;
+        *existing = todo.clone();
+        Ok(())
+    }
+
+    fn delete(&mut self, id: u32) -> TodoResult<()> {
+        let original_len = self.todos.len();
+        self.todos.retain(|t| t.id() != id);
+        if self.todos.len() < original_len {
+            Ok(())
+        } else {
+            Err(TodoError::TodoNotFound { id })
+        }
+    }
+
+    fn query(&mut self, query: &ListQuery) -> TodoResult<Vec<TodoItem>> {
+        Ok(self
+            .todos
+            .iter()
+            .filter(|t| query.item_passes_filters(t))
+            .cloned()
+            .collect())
+    }
+
+    fn meta(&self, key: &str) -> TodoResult<Option<Value>> {
+        Ok(self.meta.get(key).cloned())
+    }
+
+    fn set_meta(&mut self, key: &str, value: Value) -> TodoResult<()> {
+        self.meta.insert(key.to_string(), value);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todo::Priority;
+
+    fn todo(id: u32, completed: bool, priority: Option<Priority>) -> TodoItem {
+        TodoItem::new(
+            id,
+            format!("Task {id}"),
+            None,
+            completed,
+            None,
+            priority,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_insert_update_delete() {
+        let mut store = InMemoryStore::new();
+        store.insert(&todo(1, false, None)).unwrap();
+        store.insert(&todo(2, false, None)).unwrap();
+
+        store.update(&todo(1, true, None)).unwrap();
+        assert!(store.todos()[0].completed());
+
+        store.delete(2).unwrap();
+        assert_eq!(store.load().unwrap().len(), 1);
+        assert_eq!(store.next_id(), 3);
+    }
+
+    #[test]
+    fn test_update_and_delete_missing() {
+        let mut store = InMemoryStore::new();
+
+        assert!(matches!(
+            store.update(&todo(7, false, None)),
+            Err(TodoError::TodoNotFound { id: 7 })
+        ));
+        assert!(matches!(
+            store.delete(7),
+            Err(TodoError::TodoNotFound { id: 7 })
+        ));
+    }
+
+    #[test]
+    fn test_query_applies_filters() {
+        let mut store = InMemoryStore::with_todos(vec![
+            todo(1, false, Some(Priority::High)),
+            todo(2, true, Some(Priority::High)),
+            todo(3, false, Some(Priority::Low)),
+        ]);
+
+        let query = ListQuery {
+            only_pending: true,
+            priority: Some(Priority::High),
+            ..Default::default()
+        };
+
+        let result = store.query(&query).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].id(), 1);
+    }
+}
@@ -0,0 +1,224 @@
+use crate::todo::{TodoError, TodoResult};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+// Version written by this build. Bump it together with a new entry in
+// `MIGRATIONS` whenever the on-disk layout changes.
+pub const CURRENT_VERSION: u32 = 4;
+
+type Migration = fn(Value) -> TodoResult<Value>;
+
+// `MIGRATIONS[n]` upgrades a version `n` document to version `n + 1`
+const MIGRATIONS: &[Migration] = &[
+    v0_bare_array_to_envelope,
+    v1_add_uuids,
+    v2_add_completed_at,
+    v3_completed_to_status,
+];
+
+// Upgrade a parsed todo file to `CURRENT_VERSION`
+pub fn migrate(mut document: Value) -> TodoResult<Value> {
+    let mut version = document_version(&document)?;
+
+    if version > CURRENT_VERSION {
+        return Err(TodoError::UnsupportedVersion {
+            found: version,
+            supported: CURRENT_VERSION,
+        });
+    }
+
+    while version < CURRENT_VERSION {
+        document = MIGRATIONS[version as usize](document)?;
+        version += 1;
+    }
+    Ok(document)
+}
+
+// Files written before versioning are a bare array of todos (version 0)
+fn document_version(document: &Value) -> TodoResult<u32> {
+    match document {
+        Value::Array(_) => Ok(0),
+        Value::Object(map) => map
+            .get("version")
+            .and_then(Value::as_u64)
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| invalid("missing or invalid 'version' field")),
+        _ => Err(invalid("expected an object or an array of todos")),
+    }
+}
+
+fn invalid(reason: &str) -> TodoError {
+    TodoError::SerializationError(serde::de::Error::custom(reason))
+}
+
+fn v0_bare_array_to_envelope(document: Value) -> TodoResult<Value> {
+    let next_id = document
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|todo| todo.get("id").and_then(Value::as_u64))
+        .max()
+        .unwrap_or(0)
+        + 1;
+
+    Ok(json!({
+        "version": 1,
+        "next_id": next_id,
+        "todos": document,
+    }))
+}
+
+fn todos_mut(document: &mut Value) -> impl Iterator<Item = &mut serde_json::Map<String, Value>> {
+    document
+        .get_mut("todos")
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object_mut)
+}
+
+fn v1_add_uuids(mut document: Value) -> TodoResult<Value> {
+    for todo in todos_mut(&mut document) {
+        todo.entry("uuid")
+            .or_insert_with(|| Value::String(Uuid::new_v4().to_string()));
+    }
+    document["version"] = json!(2);
+    Ok(document)
+}
+
+// Todos completed before `completed_at` existed get their last update time
+fn v2_add_completed_at(mut document: Value) -> TodoResult<Value> {
+    for todo in todos_mut(&mut document) {
+        let completed = todo.get("completed").and_then(Value::as_bool) == Some(true);
+        if completed && !todo.contains_key("completed_at") {
+            let updated_at = todo.get("updated_at").cloned().unwrap_or(Value::Null);
+            todo.insert("completed_at".to_string(), updated_at);
+        }
+    }
+    document["version"] = json!(3);
+    Ok(document)
+}
+
+// The `completed` flag becomes a `status` of Done or Pending
+fn v3_completed_to_status(mut document: Value) -> TodoResult<Value> {
+    for todo in todos_mut(&mut document) {
+        if let Some(completed) = todo.remove("completed") {
+            let status = if completed.as_bool() == Some(true) {
+                "Done"
+            } else {
+                "Pending"
+            };
+            todo.entry("status").or_insert_with(|| json!(status));
+        }
+    }
+    document["version"] = json!(4);
+    Ok(document)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bare_array_is_wrapped() {
+        let document = json!([{"id": 3}, {"id": 7}]);
+
+        let migrated = migrate(document).unwrap();
+
+        assert_eq!(migrated["version"], CURRENT_VERSION);
+        assert_eq!(migrated["next_id"], 8);
+        assert_eq!(migrated["todos"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_uuids_are_added_and_kept() {
+        let existing = Uuid::new_v4().to_string();
+        let document = json!({
+            "version": 1,
+            "next_id": 3,
+            "todos": [{"id": 1}, {"id": 2, "uuid": existing}],
+        });
+
+        let migrated = migrate(document).unwrap();
+        let todos = migrated["todos"].as_array().unwrap();
+
+        assert!(Uuid::parse_str(todos[0]["uuid"].as_str().unwrap()).is_ok());
+        assert_eq!(todos[1]["uuid"], existing);
+    }
+
+    #[test]
+    fn test_completed_at_backfilled() {
+        let document = json!({
+            "version": 2,
+            "next_id": 3,
+            "todos": [
+                {"id": 1, "completed": true, "updated_at": "2024-05-01T10:00:00"},
+                {"id": 2, "completed": false, "updated_at": "2024-05-02T10:00:00"},
+            ],
+        });
+
+        let migrated = migrate(document).unwrap();
+        let todos = migrated["todos"].as_array().unwrap();
+
+        assert_eq!(todos[0]["completed_at"], "2024-05-01T10:00:00");
+        assert!(todos[1].get("completed_at").is_none());
+    }
+
+    #[test]
+    fn test_completed_becomes_status() {
+        let document = json!({
+            "version": 3,
+            "next_id": 3,
+            "todos": [{"id": 1, "completed": true}, {"id": 2, "completed": false}],
+        });
+
+        let migrated = migrate(document).unwrap();
+        let todos = migrated["todos"].as_array().unwrap();
+
+        assert_eq!(todos[0]["status"], "Done");
+        assert_eq!(todos[1]["status"], "Pending");
+        assert!(todos[0].get("completed").is_none());
+    }
+
+    #[test]
+    fn test_empty_bare_array() {
+        let migrated = migrate(json!([])).unwrap();
+        assert_eq!(migrated["next_id"], 1);
+    }
+
+    #[test]
+    fn test_current_version_is_unchanged() {
+        let document = json!({"version": CURRENT_VERSION, "next_id": 4, "todos": []});
+        assert_eq!(migrate(document.clone()).unwrap(), document);
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let document = json!({"version": CURRENT_VERSION + 1, "next_id": 1, "todos": []});
+
+        match migrate(document) {
+            Err(TodoError::UnsupportedVersion { found, supported }) => {
+                assert_eq!(found, CURRENT_VERSION + 1);
+                assert_eq!(supported, CURRENT_VERSION);
+            }
+            other => panic!("Expected UnsupportedVersion, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_missing_version_is_rejected() {
+        assert!(matches!(
+            migrate(json!({"todos": []})),
+            Err(TodoError::SerializationError(_))
+        ));
+        assert!(matches!(
+            migrate(json!("todos")),
+            Err(TodoError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_every_version_has_a_migration() {
+        assert_eq!(MIGRATIONS.len(), CURRENT_VERSION as usize);
+    }
+}
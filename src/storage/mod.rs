@@ -0,0 +1,169 @@
+pub mod json;
+pub mod lock;
+pub mod memory;
+pub mod migrate;
+pub mod sqlite;
+
+pub use json::{JsonFileStore, TodoFile};
+pub use lock::FileLock;
+pub use memory::InMemoryStore;
+pub use sqlite::SqliteStore;
+
+use crate::todo::{ListQuery, TodoError, TodoItem, TodoResult};
+use serde_json::Value;
+use std::path::Path;
+use std::str::FromStr;
+
+// Persistence backend behind `TodoManager`. Implementations own the on-disk
+// (or in-memory) representation; the manager only sees whole items.
+pub trait TodoStore {
+    // Human readable location of the store, e.g. the file path
+    fn location(&self) -> &str;
+
+    // File used to serialise concurrent access, `None` if the store is not shared
+    fn lock_path(&self) -> Option<String> {
+        None
+    }
+
+    fn load(&mut self) -> TodoResult<Vec<TodoItem>>;
+
+    // Id for the next new todo. Persisted and never decreases, so ids of
+    // deleted todos are not handed out again
+    fn next_id(&self) -> u32;
+
+    // Replace the whole collection
+    fn save(&mut self, todos: &[TodoItem]) -> TodoResult<()>;
+
+    fn insert(&mut self, todo: &TodoItem) -> TodoResult<()>;
+
+    fn update(&mut self, todo: &TodoItem) -> TodoResult<()>;
+
+    fn delete(&mut self, id: u32) -> TodoResult<()>;
+
+    // Return the items matching the query filters (sorting is left to the caller)
+    fn query(&mut self, query: &ListQuery) -> TodoResult<Vec<TodoItem>>;
+
+    // Named JSON values kept alongside the todos, e.g. the undo journal
+    fn meta(&self, key: &str) -> TodoResult<Option<Value>>;
+
+    fn set_meta(&mut self, key: &str, value: Value) -> TodoResult<()>;
+
+    // Group the writes that follow until `commit` into a single save, or
+    // drop them all with `rollback`. Stores that write nothing to disk can
+    // rely on the defaults.
+    fn begin(&mut self) -> TodoResult<()> {
+        Ok(())
+    }
+
+    fn commit(&mut self) -> TodoResult<()> {
+        Ok(())
+    }
+
+    fn rollback(&mut self) -> TodoResult<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Backend {
+    Json,
+    Sqlite,
+}
+
+impl Backend {
+    // Pick a backend from the file extension, defaulting to JSON
+    pub fn from_path(file_path: &str) -> Self {
+        match Path::new(file_path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .as_deref()
+        {
+            Some("db" | "sqlite" | "sqlite3") => Backend::Sqlite,
+            _ => Backend::Json,
+        }
+    }
+}
+
+impl FromStr for Backend {
+    type Err = TodoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Backend::Json),
+            "sqlite" => Ok(Backend::Sqlite),
+            _ => Err(TodoError::InvalidBackend {
+                input: s.to_string(),
+            }),
+        }
+    }
+}
+
+// Sibling file for archived todos: `todos.json` -> `todos.archive.json`
+pub fn archive_path(file_path: &str) -> String {
+    let path = Path::new(file_path);
+    match (path.file_stem(), path.extension()) {
+        (Some(stem), Some(ext)) => path
+            .with_file_name(format!(
+                "{}.archive.{}",
+                stem.to_string_lossy(),
+                ext.to_string_lossy()
+            ))
+            .to_string_lossy()
+            .into_owned(),
+        _ => format!("{file_path}.archive"),
+    }
+}
+
+pub fn open_store(file_path: String, backend: Option<Backend>) -> TodoResult<Box<dyn TodoStore>> {
+    match backend.unwrap_or_else(|| Backend::from_path(&file_path)) {
+        Backend::Json => Ok(Box::new(JsonFileStore::new(file_path))),
+        Backend::Sqlite => Ok(Box::new(SqliteStore::open(file_path)?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_backend_from_path() {
+        assert_eq!(Backend::from_path("todo_list.json"), Backend::Json);
+        assert_eq!(Backend::from_path("todos"), Backend::Json);
+        assert_eq!(Backend::from_path("todos.db"), Backend::Sqlite);
+        assert_eq!(Backend::from_path("todos.SQLITE3"), Backend::Sqlite);
+    }
+
+    #[test]
+    fn test_archive_path() {
+        assert_eq!(archive_path("todo_list.json"), "todo_list.archive.json");
+        assert_eq!(archive_path("data/todos.db"), "data/todos.archive.db");
+        assert_eq!(archive_path("todos"), "todos.archive");
+        assert_eq!(
+            Backend::from_path(&archive_path("todos.db")),
+            Backend::Sqlite
+        );
+    }
+
+    #[test]
+    fn test_backend_from_str() {
+        assert_eq!("json".parse::<Backend>().unwrap(), Backend::Json);
+        assert_eq!("SQLite".parse::<Backend>().unwrap(), Backend::Sqlite);
+
+        let result = "csv".parse::<Backend>();
+        assert!(matches!(result, Err(TodoError::InvalidBackend { input }) if input == "csv"));
+    }
+
+    #[test]
+    fn test_open_store_explicit_backend_overrides_extension() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("todos.json").to_string_lossy().into_owned();
+
+        let mut store = open_store(path.clone(), Some(Backend::Sqlite)).unwrap();
+        store.load().unwrap();
+
+        let header = std::fs::read(&path).unwrap();
+        assert!(header.starts_with(b"SQLite format 3"));
+    }
+}
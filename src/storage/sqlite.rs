@@ -0,0 +1,952 @@
+use crate::storage::TodoStore;
+use crate::todo::{
+    normalize_tag, Condition, FilterExpr, ListQuery, TodoError, TodoItem, TodoResult,
+};
+use chrono::{Duration, Local, NaiveDateTime};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, ToSql, Transaction};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+// Tracked with `PRAGMA user_version`; `MIGRATIONS[n]` upgrades a version `n`
+// database to version `n + 1`.
+const SCHEMA_VERSION: u32 = 5;
+
+type Migration = fn(&Transaction) -> TodoResult<()>;
+
+const MIGRATIONS: &[Migration] = &[
+    v0_create_tables,
+    v1_add_meta,
+    v2_add_completed_at,
+    v3_add_status,
+    v4_normalize_tags,
+];
+
+// Each row keeps the full item as JSON in `data`; the other columns are
+// copies of the fields we filter and sort on so they can be indexed.
+fn v0_create_tables(tx: &Transaction) -> TodoResult<()> {
+    tx.execute_batch(
+        "
+        CREATE TABLE todos (
+            id INTEGER PRIMARY KEY,
+            title TEXT NOT NULL,
+            completed INTEGER NOT NULL DEFAULT 0,
+            due_date TEXT,
+            priority INTEGER,
+            updated_at TEXT NOT NULL,
+            data TEXT NOT NULL
+        );
+        CREATE TABLE tags (
+            todo_id INTEGER NOT NULL REFERENCES todos(id) ON DELETE CASCADE,
+            tag TEXT NOT NULL,
+            PRIMARY KEY (todo_id, tag)
+        );
+        CREATE INDEX idx_todos_due_date ON todos(due_date);
+        CREATE INDEX idx_todos_priority ON todos(priority);
+        CREATE INDEX idx_tags_tag ON tags(tag);
+        ",
+    )?;
+    Ok(())
+}
+
+// Key/value store for JSON encoded settings such as the id counter
+fn v1_add_meta(tx: &Transaction) -> TodoResult<()> {
+    tx.execute_batch(
+        "
+        CREATE TABLE meta (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+        INSERT INTO meta (key, value)
+            SELECT 'next_id', COALESCE(MAX(id), 0) + 1 FROM todos;
+        ",
+    )?;
+
+    let rows: Vec<(u32, String)> = tx
+        .prepare("SELECT id, data FROM todos")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+    for (id, data) in rows {
+        let mut todo: Value = serde_json::from_str(&data)?;
+        if let Some(fields) = todo.as_object_mut() {
+            fields
+                .entry("uuid")
+                .or_insert_with(|| Value::String(Uuid::new_v4().to_string()));
+        }
+        tx.execute(
+            "UPDATE todos SET data = ?1 WHERE id = ?2",
+            params![todo.to_string(), id],
+        )?;
+    }
+    Ok(())
+}
+
+// Completed rows written before `completed_at` existed use their last update time
+fn v2_add_completed_at(tx: &Transaction) -> TodoResult<()> {
+    tx.execute_batch(
+        "
+        ALTER TABLE todos ADD COLUMN completed_at TEXT;
+        UPDATE todos
+            SET completed_at = updated_at,
+                data = json_set(data, '$.completed_at', json_extract(data, '$.updated_at'))
+            WHERE completed = 1 AND json_extract(data, '$.completed_at') IS NULL;
+        CREATE INDEX idx_todos_completed_at ON todos(completed_at);
+        ",
+    )?;
+    Ok(())
+}
+
+// The `completed` flag becomes a `status` of Done or Pending. The column is
+// kept in sync for older readers.
+fn v3_add_status(tx: &Transaction) -> TodoResult<()> {
+    tx.execute_batch(
+        "
+        ALTER TABLE todos ADD COLUMN status TEXT NOT NULL DEFAULT 'Pending';
+        UPDATE todos SET status = CASE completed WHEN 1 THEN 'Done' ELSE 'Pending' END;
+        UPDATE todos
+            SET data = json_set(json_remove(data, '$.completed'), '$.status', status)
+            WHERE json_extract(data, '$.status') IS NULL;
+        CREATE INDEX idx_todos_status ON todos(status);
+        ",
+    )?;
+    Ok(())
+}
+
+// Tag filters are answered from the `tags` table, so it holds normalised
+// tags even for todos saved before tags were normalised
+fn v4_normalize_tags(tx: &Transaction) -> TodoResult<()> {
+    let rows: Vec<(u32, String)> = tx
+        .prepare("SELECT todo_id, tag FROM tags")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+    tx.execute("DELETE FROM tags", [])?;
+    for (id, tag) in rows {
+        SqliteStore::write_tag(tx, id, &tag)?;
+    }
+    Ok(())
+}
+
+pub struct SqliteStore {
+    path: String,
+    conn: Connection,
+    next_id: u32,
+}
+
+impl SqliteStore {
+    pub fn open(path: String) -> TodoResult<Self> {
+        let conn = Connection::open(&path)?;
+        Self::init(path, conn)
+    }
+
+    pub fn open_in_memory() -> TodoResult<Self> {
+        let conn = Connection::open_in_memory()?;
+        Self::init(":memory:".to_string(), conn)
+    }
+
+    fn init(path: String, mut conn: Connection) -> TodoResult<Self> {
+        conn.pragma_update(None, "foreign_keys", true)?;
+        Self::migrate(&mut conn)?;
+        let next_id = Self::read_meta(&conn, "next_id")?.unwrap_or(1);
+        Ok(SqliteStore {
+            path,
+            conn,
+            next_id,
+        })
+    }
+
+    fn migrate(conn: &mut Connection) -> TodoResult<()> {
+        let version: u32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            return Err(TodoError::UnsupportedVersion {
+                found: version,
+                supported: SCHEMA_VERSION,
+            });
+        }
+        if version == SCHEMA_VERSION {
+            return Ok(());
+        }
+
+        let tx = conn.transaction()?;
+        for migration in &MIGRATIONS[version as usize..] {
+            migration(&tx)?;
+        }
+        tx.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn read_meta<T: DeserializeOwned>(conn: &Connection, key: &str) -> TodoResult<Option<T>> {
+        let value: Option<String> = conn
+            .query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        value
+            .map(|v| serde_json::from_str(&v).map_err(TodoError::SerializationError))
+            .transpose()
+    }
+
+    fn write_meta<T: Serialize>(conn: &Connection, key: &str, value: &T) -> TodoResult<()> {
+        conn.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+            params![key, serde_json::to_string(value)?],
+        )?;
+        Ok(())
+    }
+
+    // Raise the persisted id counter past `id`; returns the new value
+    fn bump_next_id(tx: &Connection, current: u32, id: u32) -> TodoResult<u32> {
+        let next_id = current.max(id + 1);
+        if next_id != current {
+            Self::write_meta(tx, "next_id", &next_id)?;
+        }
+        Ok(next_id)
+    }
+
+    fn write_row(tx: &Connection, todo: &TodoItem) -> TodoResult<()> {
+        let data = serde_json::to_string(todo)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO todos
+                (id, title, completed, status, due_date, priority, updated_at, completed_at, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                todo.id(),
+                todo.title(),
+                todo.completed(),
+                format!("{:?}", todo.status()),
+                todo.due_date(),
+                todo.priority().map(|p| p as i64),
+                todo.updated_at(),
+                todo.completed_at(),
+                data,
+            ],
+        )?;
+
+        tx.execute("DELETE FROM tags WHERE todo_id = ?1", params![todo.id()])?;
+        for tag in todo.tags().unwrap_or_default() {
+            Self::write_tag(tx, todo.id(), tag)?;
+        }
+        Ok(())
+    }
+
+    fn write_tag(tx: &Connection, id: u32, tag: &str) -> TodoResult<()> {
+        let tag = normalize_tag(tag);
+        if !tag.is_empty() {
+            tx.execute(
+                "INSERT OR IGNORE INTO tags (todo_id, tag) VALUES (?1, ?2)",
+                params![id, tag],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn select(&self, clause: &str, values: &[Box<dyn ToSql>]) -> TodoResult<Vec<TodoItem>> {
+        let sql = format!("SELECT data FROM todos {clause} ORDER BY id");
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values.iter()), |row| {
+            row.get::<_, String>(0)
+        })?;
+
+        let mut todos = Vec::new();
+        for data in rows {
+            todos.push(serde_json::from_str(&data?)?);
+        }
+        Ok(todos)
+    }
+}
+
+// Translate the filters of a query into a WHERE clause. Anything not
+// expressed here is still checked in memory by `ListQuery::item_passes_filters`.
+fn where_clause(query: &ListQuery) -> (String, Vec<Box<dyn ToSql>>) {
+    let mut conditions: Vec<String> = Vec::new();
+    let mut values: Vec<Box<dyn ToSql>> = Vec::new();
+
+    conditions.push(if query.trashed {
+        "json_extract(data, '$.deleted_at') IS NOT NULL".to_string()
+    } else {
+        "json_extract(data, '$.deleted_at') IS NULL".to_string()
+    });
+
+    match (query.only_complete, query.only_pending) {
+        (true, false) => conditions.push("status = 'Done'".to_string()),
+        (false, true) => conditions.push("status NOT IN ('Done', 'Cancelled')".to_string()),
+        _ => {}
+    }
+
+    if !query.status.is_empty() {
+        let placeholders = vec!["?"; query.status.len()].join(", ");
+        conditions.push(format!("status IN ({placeholders})"));
+        for status in &query.status {
+            values.push(Box::new(format!("{status:?}")));
+        }
+    }
+
+    if let Some(priority) = query.priority {
+        conditions.push("priority = ?".to_string());
+        values.push(Box::new(priority as i64));
+    }
+
+    if let Some(since) = query.completed_since {
+        conditions.push("completed_at >= ?".to_string());
+        values.push(Box::new(since));
+    }
+    if let Some(before) = query.completed_before {
+        conditions.push("completed_at < ?".to_string());
+        values.push(Box::new(before));
+    }
+
+    for tag in &query.tags {
+        conditions.push(tag_exists(1));
+        values.push(Box::new(tag.clone()));
+    }
+    if !query.any_tags.is_empty() {
+        conditions.push(tag_exists(query.any_tags.len()));
+        values.extend(
+            query
+                .any_tags
+                .iter()
+                .map(|t| Box::new(t.clone()) as Box<dyn ToSql>),
+        );
+    }
+    if !query.no_tags.is_empty() {
+        conditions.push(format!("NOT {}", tag_exists(query.no_tags.len())));
+        values.extend(
+            query
+                .no_tags
+                .iter()
+                .map(|t| Box::new(t.clone()) as Box<dyn ToSql>),
+        );
+    }
+    if query.untagged {
+        conditions.push(format!("NOT {}", tag_exists(0)));
+    }
+    if let Some((sql, tags)) = query.expr.as_ref().and_then(expr_prefilter) {
+        conditions.push(sql);
+        values.extend(tags.into_iter().map(|t| Box::new(t) as Box<dyn ToSql>));
+    }
+
+    let now = Local::now().naive_local();
+    let day_start = |offset: i64| -> NaiveDateTime {
+        (now.date() + Duration::days(offset))
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    };
+
+    let mut time_conditions: Vec<&str> = Vec::new();
+    if query.overdue {
+        time_conditions.push("due_date < ?");
+        values.push(Box::new(now));
+    }
+    if query.due_today {
+        time_conditions.push("(due_date >= ? AND due_date < ?)");
+        values.push(Box::new(day_start(0)));
+        values.push(Box::new(day_start(1)));
+    }
+    if query.due_tomorrow {
+        time_conditions.push("(due_date >= ? AND due_date < ?)");
+        values.push(Box::new(day_start(1)));
+        values.push(Box::new(day_start(2)));
+    }
+    if let Some(days) = query.due_within {
+        time_conditions.push("(due_date >= ? AND due_date < ?)");
+        values.push(Box::new(day_start(0)));
+        values.push(Box::new(day_start(days.saturating_add(1))));
+    }
+    if !time_conditions.is_empty() {
+        conditions.push(format!("({})", time_conditions.join(" OR ")));
+    }
+
+    if conditions.is_empty() {
+        (String::new(), values)
+    } else {
+        (format!("WHERE {}", conditions.join(" AND ")), values)
+    }
+}
+
+// Whether the todo has one of `count` tags bound as parameters, or any tag
+// at all when `count` is 0
+fn tag_exists(count: usize) -> String {
+    let mut sql = "EXISTS (SELECT 1 FROM tags WHERE tags.todo_id = todos.id".to_string();
+    if count > 0 {
+        sql.push_str(&format!(
+            " AND tags.tag IN ({})",
+            vec!["?"; count].join(", ")
+        ));
+    }
+    sql.push(')');
+    sql
+}
+
+// SQL satisfied by every todo matching a `--where` expression, along with
+// the tags it binds. Only tag conditions are translated; the expression is
+// still checked in memory, so the SQL may let through more than it matches.
+fn expr_prefilter(expr: &FilterExpr) -> Option<(String, Vec<String>)> {
+    match expr {
+        FilterExpr::And(a, b) => match (expr_prefilter(a), expr_prefilter(b)) {
+            (Some((a, mut tags)), Some((b, more))) => {
+                tags.extend(more);
+                Some((format!("({a} AND {b})"), tags))
+            }
+            (one, other) => one.or(other),
+        },
+        FilterExpr::Or(a, b) => {
+            let (a, mut tags) = expr_prefilter(a)?;
+            let (b, more) = expr_prefilter(b)?;
+            tags.extend(more);
+            Some((format!("({a} OR {b})"), tags))
+        }
+        // Negation needs the exact set, not a superset
+        FilterExpr::Not(inner) => expr_exact(inner).map(|(sql, tags)| (format!("NOT {sql}"), tags)),
+        FilterExpr::Condition(_) => expr_exact(expr),
+    }
+}
+
+// SQL matching exactly the todos `expr` matches, if it can be expressed
+fn expr_exact(expr: &FilterExpr) -> Option<(String, Vec<String>)> {
+    match expr {
+        FilterExpr::And(a, b) | FilterExpr::Or(a, b) => {
+            let operator = if matches!(expr, FilterExpr::And(..)) {
+                "AND"
+            } else {
+                "OR"
+            };
+            let (a, mut tags) = expr_exact(a)?;
+            let (b, more) = expr_exact(b)?;
+            tags.extend(more);
+            Some((format!("({a} {operator} {b})"), tags))
+        }
+        FilterExpr::Not(inner) => expr_exact(inner).map(|(sql, tags)| (format!("NOT {sql}"), tags)),
+        FilterExpr::Condition(Condition::Tag(tag)) => Some((tag_exists(1), vec![tag.clone()])),
+        FilterExpr::Condition(_) => None,
+    }
+}
+
+impl TodoStore for SqliteStore {
+    fn location(&self) -> &str {
+        &self.path
+    }
+
+    fn lock_path(&self) -> Option<String> {
+        (self.path != ":memory:").then(|| format!("{}.lock", self.path))
+    }
+
+    fn load(&mut self) -> TodoResult<Vec<TodoItem>> {
+        self.select("", &[])
+    }
+
+    fn next_id(&self) -> u32 {
+        self.next_id
+    }
+
+    fn save(&mut self, todos: &[TodoItem]) -> TodoResult<()> {
+        let tx = self.conn.savepoint()?;
+        tx.execute("DELETE FROM tags", [])?;
+        tx.execute("DELETE FROM todos", [])?;
+        for todo in todos {
+            Self::write_row(&tx, todo)?;
+        }
+        let max_id = todos.iter().map(|t| t.id()).max().unwrap_or(0);
+        let next_id = Self::bump_next_id(&tx, self.next_id, max_id)?;
+        tx.commit()?;
+        self.next_id = next_id;
+        Ok(())
+    }
+
+    fn insert(&mut self, todo: &TodoItem) -> TodoResult<()> {
+        let tx = self.conn.savepoint()?;
+        Self::write_row(&tx, todo)?;
+        let next_id = Self::bump_next_id(&tx, self.next_id, todo.id())?;
+        tx.commit()?;
+        self.next_id = next_id;
+        Ok(())
+    }
+
+    fn update(&mut self, todo: &TodoItem) -> TodoResult<()> {
+        let tx = self.conn.savepoint()?;
+        let exists: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM todos WHERE id = ?1)",
+            params![todo.id()],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(TodoError::TodoNotFound { id: todo.id() });
+        }
+        Self::write_row(&tx, todo)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn delete(&mut self, id: u32) -> TodoResult<()> {
+        let deleted = self
+            .conn
+            .execute("DELETE FROM todos WHERE id = ?1", params![id])?;
+        if deleted == 0 {
+            return Err(TodoError::TodoNotFound { id });
+        }
+        Ok(())
+    }
+
+    fn query(&mut self, query: &ListQuery) -> TodoResult<Vec<TodoItem>> {
+        let (clause, values) = where_clause(query);
+        let todos = self.select(&clause, &values)?;
+        Ok(todos
+            .into_iter()
+            .filter(|t| query.item_passes_filters(t))
+            .collect())
+    }
+
+    fn meta(&self, key: &str) -> TodoResult<Option<Value>> {
+        Self::read_meta(&self.conn, key)
+    }
+
+    fn set_meta(&mut self, key: &str, value: Value) -> TodoResult<()> {
+        Self::write_meta(&self.conn, key, &value)
+    }
+
+    // Single writes run in savepoints, which nest inside this transaction
+    fn begin(&mut self) -> TodoResult<()> {
+        self.conn.execute_batch("BEGIN")?;
+        Ok(())
+    }
+
+    fn commit(&mut self) -> TodoResult<()> {
+        self.conn.execute_batch("COMMIT")?;
+        Ok(())
+    }
+
+    fn rollback(&mut self) -> TodoResult<()> {
+        self.conn.execute_batch("ROLLBACK")?;
+        self.next_id = Self::read_meta(&self.conn, "next_id")?.unwrap_or(1);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todo::{Priority, Status};
+    use tempfile::TempDir;
+
+    fn todo(
+        id: u32,
+        completed: bool,
+        due_date: Option<NaiveDateTime>,
+        priority: Option<Priority>,
+    ) -> TodoItem {
+        TodoItem::new(
+            id,
+            format!("Task {id}"),
+            None,
+            completed,
+            due_date,
+            priority,
+            Some(vec!["work".to_string()]),
+        )
+    }
+
+    fn empty_query() -> ListQuery {
+        ListQuery::default()
+    }
+
+    #[test]
+    fn test_roundtrip_through_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("todos.db").to_string_lossy().into_owned();
+
+        let mut store = SqliteStore::open(path.clone()).unwrap();
+        store
+            .insert(&todo(1, false, None, Some(Priority::High)))
+            .unwrap();
+        store.insert(&todo(2, true, None, None)).unwrap();
+        drop(store);
+
+        let mut reopened = SqliteStore::open(path).unwrap();
+        let todos = reopened.load().unwrap();
+        assert_eq!(todos.len(), 2);
+        assert_eq!(todos[0].priority(), Some(Priority::High));
+        assert_eq!(todos[0].tags(), Some(["work".to_string()].as_slice()));
+        assert!(todos[1].completed());
+    }
+
+    #[test]
+    fn test_update_and_delete() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        store.insert(&todo(1, false, None, None)).unwrap();
+
+        store.update(&todo(1, true, None, None)).unwrap();
+        assert!(store.load().unwrap()[0].completed());
+
+        store.delete(1).unwrap();
+        assert!(store.load().unwrap().is_empty());
+
+        let tag_rows: i64 = store
+            .conn
+            .query_row("SELECT COUNT(*) FROM tags", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(tag_rows, 0);
+    }
+
+    #[test]
+    fn test_update_and_delete_missing() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+
+        assert!(matches!(
+            store.update(&todo(9, false, None, None)),
+            Err(TodoError::TodoNotFound { id: 9 })
+        ));
+        assert!(matches!(
+            store.delete(9),
+            Err(TodoError::TodoNotFound { id: 9 })
+        ));
+    }
+
+    #[test]
+    fn test_save_replaces_collection() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        store.insert(&todo(1, false, None, None)).unwrap();
+
+        store.save(&[todo(3, false, None, None)]).unwrap();
+
+        let todos = store.load().unwrap();
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[0].id(), 3);
+    }
+
+    #[test]
+    fn test_query_status_and_priority() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        store
+            .save(&[
+                todo(1, false, None, Some(Priority::High)),
+                todo(2, true, None, Some(Priority::High)),
+                todo(3, false, None, Some(Priority::Low)),
+            ])
+            .unwrap();
+
+        let mut query = empty_query();
+        query.only_pending = true;
+        query.priority = Some(Priority::High);
+
+        let result = store.query(&query).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].id(), 1);
+    }
+
+    fn tagged(id: u32, tags: &[&str]) -> TodoItem {
+        let tags = tags.iter().map(|t| t.to_string()).collect();
+        TodoItem::new(
+            id,
+            format!("Task {id}"),
+            None,
+            false,
+            None,
+            None,
+            Some(tags),
+        )
+    }
+
+    fn ids(todos: Vec<TodoItem>) -> Vec<u32> {
+        todos.iter().map(|t| t.id()).collect()
+    }
+
+    #[test]
+    fn test_query_tag_filters() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        store
+            .save(&[
+                tagged(1, &["work", "urgent"]),
+                tagged(2, &["Work"]),
+                tagged(3, &["home"]),
+                tagged(4, &[]),
+            ])
+            .unwrap();
+        let strings = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect();
+
+        let all = ListQuery {
+            tags: strings(&["work", "urgent"]),
+            ..Default::default()
+        };
+        assert_eq!(ids(store.query(&all).unwrap()), vec![1]);
+        let any = ListQuery {
+            any_tags: strings(&["urgent", "home"]),
+            ..Default::default()
+        };
+        assert_eq!(ids(store.query(&any).unwrap()), vec![1, 3]);
+        let none = ListQuery {
+            no_tags: strings(&["work"]),
+            ..Default::default()
+        };
+        assert_eq!(ids(store.query(&none).unwrap()), vec![3, 4]);
+        let untagged = ListQuery {
+            untagged: true,
+            ..Default::default()
+        };
+        assert_eq!(ids(store.query(&untagged).unwrap()), vec![4]);
+    }
+
+    #[test]
+    fn test_where_tag_conditions_become_sql() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        store
+            .save(&[tagged(1, &["work"]), tagged(2, &["home"]), tagged(3, &[])])
+            .unwrap();
+        let query = |expr: &str| ListQuery {
+            expr: Some(FilterExpr::parse(expr, &Default::default()).unwrap()),
+            ..Default::default()
+        };
+
+        let (clause, values) = where_clause(&query("tag:work and not tag:home"));
+        assert!(clause.contains("NOT EXISTS (SELECT 1 FROM tags"));
+        assert_eq!(values.len(), 2);
+        // Conditions without a column are left to the in-memory check
+        let (clause, _) = where_clause(&query("tag:work or title:task"));
+        assert!(!clause.contains("FROM tags"));
+
+        assert_eq!(
+            ids(store.query(&query("not tag:work")).unwrap()),
+            vec![2, 3]
+        );
+        assert_eq!(
+            ids(store.query(&query("tag:home or title:'task 1'")).unwrap()),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn test_query_separates_trash() {
+        let mut trashed = todo(2, false, None, None);
+        trashed.set_deleted_at(Some(Local::now().naive_local()));
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        store.save(&[todo(1, false, None, None), trashed]).unwrap();
+
+        let live = store.query(&empty_query()).unwrap();
+        assert_eq!(live.len(), 1);
+        assert_eq!(live[0].id(), 1);
+
+        let mut query = empty_query();
+        query.trashed = true;
+        let trash = store.query(&query).unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].id(), 2);
+    }
+
+    #[test]
+    fn test_query_time_filters() {
+        let now = Local::now().naive_local();
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        store
+            .save(&[
+                todo(1, false, Some(now - Duration::days(2)), None),
+                todo(2, false, Some(now + Duration::days(1)), None),
+                todo(3, false, Some(now + Duration::days(20)), None),
+                todo(4, false, None, None),
+            ])
+            .unwrap();
+
+        let mut query = empty_query();
+        query.overdue = true;
+        query.due_tomorrow = true;
+        let ids: Vec<u32> = store
+            .query(&query)
+            .unwrap()
+            .iter()
+            .map(|t| t.id())
+            .collect();
+        assert_eq!(ids, vec![1, 2]);
+
+        let mut query = empty_query();
+        query.due_within = Some(7);
+        let ids: Vec<u32> = store
+            .query(&query)
+            .unwrap()
+            .iter()
+            .map(|t| t.id())
+            .collect();
+        assert_eq!(ids, vec![2]);
+    }
+
+    #[test]
+    fn test_query_completed_range() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let done = todo(1, true, None, None);
+        let completed_at = done.completed_at().unwrap();
+        store.save(&[done, todo(2, false, None, None)]).unwrap();
+
+        let mut query = empty_query();
+        query.completed_since = Some(completed_at);
+        let ids: Vec<u32> = store
+            .query(&query)
+            .unwrap()
+            .iter()
+            .map(|t| t.id())
+            .collect();
+        assert_eq!(ids, vec![1]);
+
+        query.completed_before = Some(completed_at);
+        assert!(store.query(&query).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_query_status() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let mut started = todo(2, false, None, None);
+        started.set_status(Status::InProgress);
+        let mut cancelled = todo(3, false, None, None);
+        cancelled.set_status(Status::Cancelled);
+        store
+            .save(&[todo(1, true, None, None), started, cancelled])
+            .unwrap();
+
+        let mut ids = |query: &ListQuery| -> Vec<u32> {
+            store.query(query).unwrap().iter().map(|t| t.id()).collect()
+        };
+
+        let mut query = empty_query();
+        query.status = vec![Status::InProgress, Status::Cancelled];
+        assert_eq!(ids(&query), vec![2, 3]);
+
+        let pending = ListQuery {
+            only_pending: true,
+            ..Default::default()
+        };
+        assert_eq!(ids(&pending), vec![2]);
+    }
+
+    #[test]
+    fn test_next_id_survives_deletes_and_reopen() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("todos.db").to_string_lossy().into_owned();
+
+        let mut store = SqliteStore::open(path.clone()).unwrap();
+        assert_eq!(store.next_id(), 1);
+        store.insert(&todo(1, false, None, None)).unwrap();
+        store.insert(&todo(2, false, None, None)).unwrap();
+        store.delete(2).unwrap();
+        store.save(&[]).unwrap();
+        assert_eq!(store.next_id(), 3);
+        drop(store);
+
+        assert_eq!(SqliteStore::open(path).unwrap().next_id(), 3);
+    }
+
+    #[test]
+    fn test_upgrades_version_1_database() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("todos.db").to_string_lossy().into_owned();
+        {
+            let mut conn = Connection::open(&path).unwrap();
+            let tx = conn.transaction().unwrap();
+            v0_create_tables(&tx).unwrap();
+            tx.execute(
+                "INSERT INTO todos (id, title, completed, updated_at, data)
+                 VALUES (4, 'Old', 1, '2024-01-01 10:00:00', ?1)",
+                [
+                    r#"{"id": 4, "title": "Old", "description": null, "completed": true,
+                    "due_date": null, "priority": null, "tags": null,
+                    "created_at": "2024-01-01T10:00:00", "updated_at": "2024-01-01T10:00:00"}"#,
+                ],
+            )
+            .unwrap();
+            tx.pragma_update(None, "user_version", 1).unwrap();
+            tx.commit().unwrap();
+        }
+
+        let mut store = SqliteStore::open(path.clone()).unwrap();
+        assert_eq!(store.next_id(), 5);
+        let loaded = store.load().unwrap();
+        let uuid = loaded[0].uuid();
+        assert_eq!(loaded[0].completed_at(), Some(loaded[0].updated_at()));
+        assert_eq!(loaded[0].status(), Status::Done);
+        let done = ListQuery {
+            status: vec![Status::Done],
+            ..Default::default()
+        };
+        assert_eq!(store.query(&done).unwrap().len(), 1);
+
+        let query = ListQuery {
+            completed_before: loaded[0]
+                .updated_at()
+                .checked_add_signed(Duration::seconds(1)),
+            ..Default::default()
+        };
+        assert_eq!(store.query(&query).unwrap().len(), 1);
+        drop(store);
+
+        let mut reopened = SqliteStore::open(path).unwrap();
+        assert_eq!(reopened.load().unwrap()[0].uuid(), uuid);
+    }
+
+    #[test]
+    fn test_upgrade_normalizes_tag_rows() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("todos.db").to_string_lossy().into_owned();
+        {
+            let mut store = SqliteStore::open(path.clone()).unwrap();
+            store.insert(&tagged(1, &["Work "])).unwrap();
+            store
+                .conn
+                .execute_batch(
+                    "UPDATE tags SET tag = 'Work ';
+                     INSERT INTO tags (todo_id, tag) VALUES (1, 'WORK');
+                     PRAGMA user_version = 4;",
+                )
+                .unwrap();
+        }
+
+        let mut store = SqliteStore::open(path).unwrap();
+        let tags: Vec<String> = store
+            .conn
+            .prepare("SELECT tag FROM tags")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(tags, vec!["work"]);
+        let work = ListQuery {
+            tags: vec!["work".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(ids(store.query(&work).unwrap()), vec![1]);
+    }
+
+    #[test]
+    fn test_refuses_newer_schema() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("todos.db").to_string_lossy().into_owned();
+        Connection::open(&path)
+            .unwrap()
+            .pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+
+        assert!(matches!(
+            SqliteStore::open(path),
+            Err(TodoError::UnsupportedVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_meta_roundtrip() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        assert_eq!(store.meta("journal").unwrap(), None);
+
+        store
+            .set_meta("journal", serde_json::json!([1, 2]))
+            .unwrap();
+        assert_eq!(
+            store.meta("journal").unwrap(),
+            Some(serde_json::json!([1, 2]))
+        );
+    }
+
+    #[test]
+    fn test_schema_version_is_recorded() {
+        let store = SqliteStore::open_in_memory().unwrap();
+        let version: u32 = store
+            .conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::todo::{TodoError, TodoResult};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// A URL or a local file referenced from a todo
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Attachment {
+    // URL as given, or the absolute path of a local file
+    pub target: String,
+    pub added_at: NaiveDateTime,
+}
+
+impl Attachment {
+    // Local paths must exist and are stored absolute, so the attachment can
+    // be opened from any directory
+    pub fn parse(input: &str, added_at: NaiveDateTime) -> TodoResult<Self> {
+        let input = input.trim();
+        let target = if is_url(input) {
+            input.to_string()
+        } else {
+            let path =
+                Path::new(input)
+                    .canonicalize()
+                    .map_err(|_| TodoError::AttachmentNotFound {
+                        path: input.to_string(),
+                    })?;
+            path.to_string_lossy().into_owned()
+        };
+        Ok(Attachment { target, added_at })
+    }
+
+    pub fn is_url(&self) -> bool {
+        is_url(&self.target)
+    }
+}
+
+// "scheme://..." or "mailto:..."
+fn is_url(input: &str) -> bool {
+    match input.split_once("://") {
+        Some((scheme, rest)) => {
+            !scheme.is_empty()
+                && !rest.is_empty()
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => input.starts_with("mailto:"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_parse_urls() {
+        let now = Local::now().naive_local();
+        for url in ["https://example.com/issue/42", "mailto:ops@example.com"] {
+            let attachment = Attachment::parse(url, now).unwrap();
+            assert_eq!(attachment.target, url);
+            assert!(attachment.is_url());
+        }
+    }
+
+    #[test]
+    fn test_parse_local_paths() {
+        let now = Local::now().naive_local();
+        let file = NamedTempFile::new().unwrap();
+        let attachment = Attachment::parse(file.path().to_str().unwrap(), now).unwrap();
+        assert!(Path::new(&attachment.target).is_absolute());
+        assert!(!attachment.is_url());
+
+        assert!(matches!(
+            Attachment::parse("no/such/file.pdf", now),
+            Err(TodoError::AttachmentNotFound { .. })
+        ));
+        assert!(matches!(
+            Attachment::parse("://nothing", now),
+            Err(TodoError::AttachmentNotFound { .. })
+        ));
+    }
+}
@@ -21,6 +21,8 @@ pub enum TodoError {
     EmptyTodo,
     #[error("❌ Failed to save/load todos")]
     SerializationError(#[from] serde_json::Error),
+    #[error("❌ Invalid status: '{input}'. Use: pending, in-progress, waiting, done, cancelled")]
+    InvalidStatus { input: String },
     #[error("❌ Invalid sort field: '{field}'. Available: due, priority, due+priority")]
     InvalidSortField { field: String },
     #[error("❌ Todo too long: {actual} characters (max: {max})")]
@@ -31,6 +33,78 @@ pub enum TodoError {
     PastDate { date: String },
     #[error("❌ Invalid date: {input}. Reason: {reason}")]
     InvalidDate { input: String, reason: String },
+    #[error("❌ Timed out after {seconds}s waiting for lock '{path}'. Another todo command may still be running")]
+    LockTimeout { path: String, seconds: f64 },
+    #[error("❌ Todo file format version {found} is newer than this build supports ({supported}). Please upgrade todo")]
+    UnsupportedVersion { found: u32, supported: u32 },
+    #[error("❌ Invalid duration: '{input}'. Use a number followed by m, h, d or w (e.g. 30d)")]
+    InvalidDuration { input: String },
+    #[error("❌ Invalid repeat rule: '{input}'. Use e.g. daily, weekly, \"every 2 weeks\" or \"every monday\"")]
+    InvalidRecurrence { input: String },
+    #[error("❌ The next occurrence of '{rule}' is past the latest supported date")]
+    RecurrenceOutOfRange { rule: String },
+    #[error("❌ '{input}' is past the supported date range")]
+    DateOutOfRange { input: String },
+    #[error("❌ Todo {id} is not in the trash")]
+    NotInTrash { id: u32 },
+    #[error("❌ Todo {parent} cannot be the parent of todo {id}: it is the todo itself or one of its subtasks")]
+    InvalidParent { id: u32, parent: u32 },
+    #[error(
+        "❌ Todo {id} cannot depend on todo {dependency}: that would create a dependency cycle"
+    )]
+    DependencyCycle { id: u32, dependency: u32 },
+    #[error("❌ Todo {id} is not in the archive")]
+    NotArchived { id: u32 },
+    #[error("❌ Todo {id} is already in the todo list")]
+    AlreadyInList { id: u32 },
+    #[error("❌ No archive is configured for this todo list")]
+    ArchiveUnavailable,
+    #[error("❌ Nothing to undo")]
+    NothingToUndo,
+    #[error("❌ Nothing to redo")]
+    NothingToRedo,
+    #[error("❌ A timer is already running on todo {id}; stop it first")]
+    TimerRunning { id: u32 },
+    #[error("❌ No timer is running")]
+    NoActiveTimer,
+    #[error("❌ File not found: '{path}'")]
+    AttachmentNotFound { path: String },
+    #[error("❌ Todo {id} has no attachments")]
+    NoAttachments { id: u32 },
+    #[error("❌ Could not open '{target}': {reason}")]
+    OpenFailed { target: String, reason: String },
+    #[error("❌ Invalid config file '{path}': {reason}")]
+    InvalidConfig { path: String, reason: String },
+    #[error("❌ Unknown attribute '{key}'. Declare it under [uda.{key}] in the config file")]
+    UnknownUda { key: String },
+    #[error("❌ Invalid value '{value}' for '{key}': expected {expected}")]
+    InvalidUdaValue {
+        key: String,
+        value: String,
+        expected: String,
+    },
+    #[error("❌ Invalid assignment: '{input}'. Use key=value")]
+    InvalidAssignment { input: String },
+    #[error("❌ Invalid attribute filter: '{input}'. Use e.g. points>=3 or customer=acme")]
+    InvalidUdaFilter { input: String },
+    #[error("❌ Invalid project: '{name}'. Use dotted names like work.backend")]
+    InvalidProject { name: String },
+    #[error("❌ Invalid assignee: '{name}'. Use a user name without spaces")]
+    InvalidAssignee { name: String },
+    #[error("❌ Could not tell who you are. Set `user` in the config file or $USER")]
+    UnknownUser,
+    #[error("❌ Reminder '{input}' is relative to the due date, but the todo has none")]
+    ReminderWithoutDue { input: String },
+    #[error("❌ Invalid query at column {column}: {message}\n   {query}\n   {}^", " ".repeat(.column.saturating_sub(1)))]
+    InvalidQuery {
+        query: String,
+        column: usize,
+        message: String,
+    },
+    #[error("❌ Invalid backend: '{input}'. Available: json, sqlite")]
+    InvalidBackend { input: String },
+    #[error("❌ Failed to access todo database")]
+    DatabaseError(#[from] rusqlite::Error),
 }
 
 impl From<std::io::Error> for TodoError {
@@ -153,6 +227,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_lock_timeout_error() {
+        let error = TodoError::LockTimeout {
+            path: "todo_list.json.lock".to_string(),
+            seconds: 5.0,
+        };
+        assert_eq!(
+            error.to_string(),
+            "❌ Timed out after 5s waiting for lock 'todo_list.json.lock'. Another todo command may still be running"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_version_error() {
+        let error = TodoError::UnsupportedVersion {
+            found: 3,
+            supported: 1,
+        };
+        assert_eq!(
+            error.to_string(),
+            "❌ Todo file format version 3 is newer than this build supports (1). Please upgrade todo"
+        );
+    }
+
+    #[test]
+    fn test_invalid_backend_error() {
+        let error = TodoError::InvalidBackend {
+            input: "csv".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "❌ Invalid backend: 'csv'. Available: json, sqlite"
+        );
+    }
+
+    #[test]
+    fn test_from_rusqlite_error() {
+        let todo_error: TodoError = rusqlite::Error::QueryReturnedNoRows.into();
+
+        assert!(matches!(todo_error, TodoError::DatabaseError(_)));
+        assert!(todo_error.source().is_some());
+    }
+
     #[test]
     fn test_todo_result_type() {
         let ok_result: TodoResult<String> = Ok("success".to_string());
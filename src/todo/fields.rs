@@ -0,0 +1,32 @@
+// Attributes accepted by `TodoManager::add_todo_with` and `edit_todo_with`.
+// `None` leaves a field unset when adding and unchanged when editing.
+#[derive(Debug, Default, Clone)]
+pub struct TodoFields {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub due_date: Option<String>,
+    pub scheduled: Option<String>,
+    // Hide the todo from `list` until this date
+    pub wait: Option<String>,
+    pub priority: Option<String>,
+    // Expected effort such as "90m" or "2h"; "none" removes it when editing
+    pub estimate: Option<String>,
+    pub tags: Option<Vec<String>>,
+    // Dotted project path; subtasks default to their parent's project.
+    // Only applied by `add_todo_with`, see `TodoManager::move_todo`.
+    pub project: Option<String>,
+    // User responsible for the todo; "none" unassigns it when editing
+    pub assignee: Option<String>,
+    pub parent: Option<u32>,
+    pub depends_on: Option<Vec<u32>>,
+    // Repeat rule such as "weekly"; "none" removes it when editing
+    pub repeat: Option<String>,
+    // Status such as "waiting"; only applied by `edit_todo_with`
+    pub status: Option<String>,
+    // Reminders such as "1h before" (the due date) or "tomorrow 9:00". When
+    // editing they replace the existing ones, and "none" removes them all.
+    pub remind: Vec<String>,
+    // `key=value` assignments of user-defined attributes; an empty value
+    // removes the attribute when editing
+    pub set: Vec<String>,
+}
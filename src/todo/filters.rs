@@ -1,17 +1,56 @@
-use crate::todo::item::{Priority, SortBy, TodoItem};
-use chrono::Local;
+use crate::todo::item::{Priority, SortBy, Status, TodoItem};
+use crate::todo::project::in_project;
+use crate::todo::query::FilterExpr;
+use crate::todo::tag::has_tag;
+use crate::todo::uda::UdaFilter;
+use chrono::{Local, NaiveDateTime};
 
+#[derive(Debug, Default)]
 pub struct ListQuery {
     pub sort_by: SortBy,
     pub asc: bool,
     pub desc: bool,
     pub only_complete: bool,
+    // Open todos, i.e. neither done nor cancelled
     pub only_pending: bool,
+    // Any of the given statuses; empty means all
+    pub status: Vec<Status>,
     pub priority: Option<Priority>,
+    // Tags, already normalised: todos must have all of `tags`, at least one
+    // of `any_tags` and none of `no_tags`
+    pub tags: Vec<String>,
+    pub any_tags: Vec<String>,
+    pub no_tags: Vec<String>,
+    pub untagged: bool,
+    // A project, including its sub-projects
+    pub project: Option<String>,
+    // Assigned to this user
+    pub assignee: Option<String>,
     pub overdue: bool,
     pub due_today: bool,
     pub due_tomorrow: bool,
     pub due_within: Option<i64>,
+    // Case-insensitive text that one of the todo's notes must contain
+    pub note_contains: Option<String>,
+    // Conditions on user-defined attributes, all of which must hold
+    pub udas: Vec<UdaFilter>,
+    // A `--where` expression
+    pub expr: Option<FilterExpr>,
+    // Scheduled to start today
+    pub scheduled_today: bool,
+    // List todos hidden behind a future wait date instead of the others
+    pub waiting: bool,
+    // Completed at or after / strictly before the given time
+    pub completed_since: Option<NaiveDateTime>,
+    pub completed_before: Option<NaiveDateTime>,
+    // Pending todos waiting on / free of unfinished dependencies. Applied by
+    // `TodoManager::query`, since they depend on other todos.
+    pub blocked: bool,
+    pub ready: bool,
+    // List the trash instead of live todos
+    pub trashed: bool,
+    // Read from the archive store instead of the main one
+    pub archived: bool,
 }
 
 impl ListQuery {
@@ -19,15 +58,38 @@ impl ListQuery {
     pub fn has_any_filters(&self) -> bool {
         self.only_complete
             || self.only_pending
+            || !self.status.is_empty()
             || self.priority.is_some()
+            || !self.tags.is_empty()
+            || !self.any_tags.is_empty()
+            || !self.no_tags.is_empty()
+            || self.untagged
+            || self.project.is_some()
+            || self.assignee.is_some()
             || self.overdue
             || self.due_today
             || self.due_tomorrow
             || self.due_within.is_some()
+            || self.scheduled_today
+            || self.note_contains.is_some()
+            || !self.udas.is_empty()
+            || self.expr.is_some()
+            || self.completed_since.is_some()
+            || self.completed_before.is_some()
     }
 
     // Check if item passes ALL active filters (AND logic)
     pub fn item_passes_filters(&self, item: &TodoItem) -> bool {
+        // Trashed and live todos are never listed together
+        if item.is_trashed() != self.trashed {
+            return false;
+        }
+
+        // Waiting todos stay out of regular listings until their wait date
+        if !self.trashed && item.is_waiting() != self.waiting {
+            return false;
+        }
+
         // If no filters are set, include all items
         if !self.has_any_filters() {
             return true;
@@ -37,14 +99,76 @@ impl ListQuery {
         self.passes_status_filter(item)
             && self.passes_priority_filter(item)
             && self.passes_time_filter(item)
+            && self.passes_completed_filter(item)
+            && self.passes_scheduled_filter(item)
+            && self.passes_note_filter(item)
+            && self.passes_uda_filter(item)
+            && self.passes_tag_filter(item)
+            && self.passes_project_filter(item)
+            && self.passes_assignee_filter(item)
+            && self.passes_expr_filter(item)
+    }
+
+    pub fn passes_expr_filter(&self, item: &TodoItem) -> bool {
+        self.expr.iter().all(|expr| expr.matches(item))
+    }
+
+    pub fn passes_assignee_filter(&self, item: &TodoItem) -> bool {
+        match &self.assignee {
+            Some(assignee) => item.assignee() == Some(assignee.as_str()),
+            None => true,
+        }
+    }
+
+    pub fn passes_tag_filter(&self, item: &TodoItem) -> bool {
+        if self.untagged && item.tags().is_some_and(|tags| !tags.is_empty()) {
+            return false;
+        }
+        self.tags.iter().all(|tag| has_tag(item, tag))
+            && (self.any_tags.is_empty() || self.any_tags.iter().any(|tag| has_tag(item, tag)))
+            && !self.no_tags.iter().any(|tag| has_tag(item, tag))
+    }
+
+    pub fn passes_project_filter(&self, item: &TodoItem) -> bool {
+        match &self.project {
+            Some(project) => in_project(item.project(), project),
+            None => true,
+        }
+    }
+
+    pub fn passes_uda_filter(&self, item: &TodoItem) -> bool {
+        self.udas.iter().all(|filter| filter.matches(item))
+    }
+
+    pub fn passes_note_filter(&self, item: &TodoItem) -> bool {
+        match &self.note_contains {
+            Some(text) => item.notes().iter().any(|note| note.contains(text)),
+            None => true,
+        }
+    }
+
+    pub fn passes_scheduled_filter(&self, item: &TodoItem) -> bool {
+        !self.scheduled_today
+            || item.scheduled().map(|d| d.date()) == Some(Local::now().naive_local().date())
     }
 
     pub fn passes_status_filter(&self, item: &TodoItem) -> bool {
-        match (self.only_complete, self.only_pending) {
+        let passes = match (self.only_complete, self.only_pending) {
             (true, false) => item.completed(),
-            (false, true) => !item.completed(),
+            (false, true) => !item.is_closed(),
             _ => true,
+        };
+        passes && (self.status.is_empty() || self.status.contains(&item.status()))
+    }
+
+    pub fn passes_completed_filter(&self, item: &TodoItem) -> bool {
+        if self.completed_since.is_none() && self.completed_before.is_none() {
+            return true;
         }
+        item.completed_at().is_some_and(|at| {
+            self.completed_since.iter().all(|since| at >= *since)
+                && self.completed_before.iter().all(|before| at < *before)
+        })
     }
 
     pub fn passes_priority_filter(&self, item: &TodoItem) -> bool {
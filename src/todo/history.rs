@@ -0,0 +1,31 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// One field change on a todo, recorded by the `set_*` setters
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+    pub timestamp: NaiveDateTime,
+}
+
+impl HistoryEntry {
+    pub fn new(
+        field: &str,
+        old: Option<String>,
+        new: Option<String>,
+        timestamp: NaiveDateTime,
+    ) -> Self {
+        HistoryEntry {
+            field: field.to_string(),
+            old,
+            new,
+            timestamp,
+        }
+    }
+}
+
+pub(crate) fn format_value(value: Option<NaiveDateTime>) -> Option<String> {
+    value.map(|v| v.format("%Y-%m-%d %H:%M").to_string())
+}
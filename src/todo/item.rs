@@ -1,20 +1,78 @@
+use crate::todo::attachment::Attachment;
 use crate::todo::error::TodoError;
-use chrono::{Local, NaiveDateTime};
-use serde::{Deserialize, Serialize};
+use crate::todo::history::{format_value, HistoryEntry};
+use crate::todo::note::Note;
+use crate::todo::recurrence::Recurrence;
+use crate::todo::reminder::Reminder;
+use crate::todo::time_log::{format_duration, TimeEntry};
+use chrono::{Duration, Local, NaiveDateTime};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
 use std::str::FromStr;
+use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TodoItem {
     id: u32,
+    // Stable across files and id reassignment, for external references
+    #[serde(default = "Uuid::new_v4")]
+    uuid: Uuid,
     title: String,
     description: Option<String>,
-    completed: bool,
+    // Files written before statuses existed store `completed: bool`
+    #[serde(alias = "completed", deserialize_with = "deserialize_status")]
+    status: Status,
     due_date: Option<NaiveDateTime>,
+    // When work on the todo is planned to start
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scheduled: Option<NaiveDateTime>,
+    // Hidden from `list` until this time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wait_until: Option<NaiveDateTime>,
     priority: Option<Priority>,
     tags: Option<Vec<String>>,
+    // Dotted project path such as `work.backend`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project: Option<String>,
+    // User responsible for the todo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    assignee: Option<String>,
     created_at: NaiveDateTime,
     updated_at: NaiveDateTime,
+    // Todo this one is a subtask of
+    #[serde(default)]
+    parent_id: Option<u32>,
+    // Todos that have to be completed before this one can start
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    depends_on: Vec<u32>,
+    // Completing a repeating todo creates its next occurrence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    recurrence: Option<Recurrence>,
+    // Expected effort in minutes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    estimate: Option<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    time_log: Vec<TimeEntry>,
+    // Append-only comments, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    notes: Vec<Note>,
+    // Linked URLs and files, in the order they were attached
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    attachments: Vec<Attachment>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    reminders: Vec<Reminder>,
+    // Values of user-defined attributes, already checked against their type
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    udas: BTreeMap<String, String>,
+    // When the todo was last marked as done
+    #[serde(default)]
+    completed_at: Option<NaiveDateTime>,
+    // Set while the todo is in the trash
+    #[serde(default)]
+    deleted_at: Option<NaiveDateTime>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    history: Vec<HistoryEntry>,
 }
 
 impl TodoItem {
@@ -31,21 +89,137 @@ impl TodoItem {
 
         TodoItem {
             id,
+            uuid: Uuid::new_v4(),
             title,
             description,
-            completed,
+            status: if completed {
+                Status::Done
+            } else {
+                Status::Pending
+            },
             due_date,
+            scheduled: None,
+            wait_until: None,
             priority,
             tags,
+            project: None,
+            assignee: None,
             created_at: now,
             updated_at: now,
+            parent_id: None,
+            depends_on: Vec::new(),
+            recurrence: None,
+            estimate: None,
+            time_log: Vec::new(),
+            notes: Vec::new(),
+            attachments: Vec::new(),
+            reminders: Vec::new(),
+            udas: BTreeMap::new(),
+            completed_at: completed.then_some(now),
+            deleted_at: None,
+            history: Vec::new(),
         }
     }
 
+    // Make the new todo a subtask of `parent_id`
+    pub fn with_parent(mut self, parent_id: Option<u32>) -> Self {
+        self.parent_id = parent_id;
+        self
+    }
+
+    pub fn with_recurrence(mut self, recurrence: Option<Recurrence>) -> Self {
+        self.recurrence = recurrence;
+        self
+    }
+
+    pub fn with_project(mut self, project: Option<String>) -> Self {
+        self.project = project;
+        self
+    }
+
+    pub fn with_assignee(mut self, assignee: Option<String>) -> Self {
+        self.assignee = assignee;
+        self
+    }
+
+    pub fn with_reminders(mut self, reminders: Vec<Reminder>) -> Self {
+        self.reminders = reminders;
+        self
+    }
+
+    pub fn with_udas(mut self, udas: BTreeMap<String, String>) -> Self {
+        self.udas = udas;
+        self
+    }
+
+    pub fn with_estimate(mut self, estimate: Option<Duration>) -> Self {
+        self.estimate = estimate.map(|e| e.num_minutes());
+        self
+    }
+
+    pub fn with_schedule(
+        mut self,
+        scheduled: Option<NaiveDateTime>,
+        wait_until: Option<NaiveDateTime>,
+    ) -> Self {
+        self.scheduled = scheduled;
+        self.wait_until = wait_until;
+        self
+    }
+
+    // Copy of a repeating todo for its next occurrence, due at `due_date`.
+    // Scheduled and wait dates keep their distance to the due date.
+    pub fn next_occurrence(&self, id: u32, due_date: Option<NaiveDateTime>) -> Self {
+        let shift = |date: Option<NaiveDateTime>| match (self.due_date, due_date) {
+            (Some(old), Some(new)) => date.and_then(|d| d.checked_add_signed(new - old)),
+            _ => None,
+        };
+        TodoItem::new(
+            id,
+            self.title.clone(),
+            self.description.clone(),
+            false,
+            due_date,
+            self.priority,
+            self.tags.clone(),
+        )
+        .with_parent(self.parent_id)
+        .with_recurrence(self.recurrence)
+        .with_schedule(shift(self.scheduled), shift(self.wait_until))
+        .with_estimate(self.estimate())
+        .with_project(self.project.clone())
+        .with_assignee(self.assignee.clone())
+        .with_udas(self.udas.clone())
+        .with_reminders(
+            // Only reminders relative to the due date carry over
+            self.reminders
+                .iter()
+                .filter(|r| r.before_due.is_some() && due_date.is_some())
+                .map(|r| {
+                    let mut reminder = Reminder {
+                        delivered: false,
+                        ..*r
+                    };
+                    reminder.follow_due(due_date);
+                    reminder
+                })
+                .collect(),
+        )
+    }
+
+    pub fn with_dependencies(mut self, depends_on: Vec<u32>) -> Self {
+        self.depends_on = depends_on;
+        self
+    }
+
     pub fn id(&self) -> u32 {
         self.id
     }
 
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
     pub fn title(&self) -> &str {
         &self.title
     }
@@ -54,8 +228,17 @@ impl TodoItem {
         self.description.as_deref()
     }
 
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
     pub fn completed(&self) -> bool {
-        self.completed
+        self.status == Status::Done
+    }
+
+    // Done or cancelled; nothing is left to do
+    pub fn is_closed(&self) -> bool {
+        matches!(self.status, Status::Done | Status::Cancelled)
     }
 
     pub fn due_date(&self) -> Option<NaiveDateTime> {
@@ -70,46 +253,297 @@ impl TodoItem {
         self.tags.as_deref()
     }
 
+    pub fn created_at(&self) -> NaiveDateTime {
+        self.created_at
+    }
+
+    pub fn updated_at(&self) -> NaiveDateTime {
+        self.updated_at
+    }
+
+    pub fn parent_id(&self) -> Option<u32> {
+        self.parent_id
+    }
+
+    pub fn depends_on(&self) -> &[u32] {
+        &self.depends_on
+    }
+
+    // Open with at least one dependency in `todos` that is neither closed
+    // nor trashed. Dependencies that no longer exist do not block.
+    pub fn is_blocked_by(&self, todos: &[TodoItem]) -> bool {
+        !self.is_closed()
+            && self.depends_on.iter().any(|dep| {
+                todos
+                    .iter()
+                    .any(|t| t.id == *dep && !t.is_closed() && !t.is_trashed())
+            })
+    }
+
+    pub fn recurrence(&self) -> Option<Recurrence> {
+        self.recurrence
+    }
+
+    pub fn scheduled(&self) -> Option<NaiveDateTime> {
+        self.scheduled
+    }
+
+    pub fn wait_until(&self) -> Option<NaiveDateTime> {
+        self.wait_until
+    }
+
+    pub fn estimate(&self) -> Option<Duration> {
+        self.estimate.and_then(Duration::try_minutes)
+    }
+
+    pub fn time_log(&self) -> &[TimeEntry] {
+        &self.time_log
+    }
+
+    pub fn notes(&self) -> &[Note] {
+        &self.notes
+    }
+
+    pub fn attachments(&self) -> &[Attachment] {
+        &self.attachments
+    }
+
+    pub fn project(&self) -> Option<&str> {
+        self.project.as_deref()
+    }
+
+    pub fn reminders(&self) -> &[Reminder] {
+        &self.reminders
+    }
+
+    pub fn assignee(&self) -> Option<&str> {
+        self.assignee.as_deref()
+    }
+
+    pub fn uda(&self, key: &str) -> Option<&str> {
+        self.udas.get(key).map(String::as_str)
+    }
+
+    pub fn udas(&self) -> &BTreeMap<String, String> {
+        &self.udas
+    }
+
+    // Total tracked time, only counting work at or after `since` if given
+    pub fn time_spent(&self, since: Option<NaiveDateTime>) -> Duration {
+        self.time_log
+            .iter()
+            .map(|entry| match since {
+                Some(since) => entry.duration_since(since),
+                None => entry.duration(),
+            })
+            .fold(Duration::zero(), |total, d| total + d)
+    }
+
+    // Open and hidden behind a wait date that has not passed yet
+    pub fn is_waiting(&self) -> bool {
+        !self.is_closed()
+            && self
+                .wait_until
+                .is_some_and(|wait| wait > Local::now().naive_local())
+    }
+
+    pub fn completed_at(&self) -> Option<NaiveDateTime> {
+        self.completed_at
+    }
+
+    pub fn deleted_at(&self) -> Option<NaiveDateTime> {
+        self.deleted_at
+    }
+
+    // Field changes made through the setters, oldest first
+    pub fn history(&self) -> &[HistoryEntry] {
+        &self.history
+    }
+
+    pub fn is_trashed(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
     pub fn tags_string(&self) -> Option<String> {
         self.tags.as_ref().map(|t| t.join(", "))
     }
 
     pub fn is_overdue(&self) -> bool {
         if let Some(due) = self.due_date {
-            due + chrono::Duration::minutes(5) < Local::now().naive_local() && !self.completed
+            due + chrono::Duration::minutes(5) < Local::now().naive_local() && !self.is_closed()
         } else {
             false
         }
     }
 
     pub fn set_title(&mut self, title: String) {
-        self.title = title;
-        self.touch();
+        let old = std::mem::replace(&mut self.title, title);
+        self.track("title", Some(old), Some(self.title.clone()));
     }
 
     pub fn set_description(&mut self, description: Option<String>) {
-        self.description = description;
-        self.touch();
+        let old = std::mem::replace(&mut self.description, description);
+        self.track("description", old, self.description.clone());
     }
 
     pub fn set_completed(&mut self, completed: bool) {
-        self.completed = completed;
-        self.touch();
+        self.set_status(if completed {
+            Status::Done
+        } else {
+            Status::Pending
+        });
+    }
+
+    // Also stamps `completed_at` when the todo becomes done, and clears it
+    // when it leaves that state
+    pub fn set_status(&mut self, status: Status) {
+        let old = std::mem::replace(&mut self.status, status);
+        self.track("status", Some(old.to_string()), Some(status.to_string()));
+        if old != status {
+            self.completed_at = (status == Status::Done).then_some(self.updated_at);
+        }
     }
 
     pub fn set_due_date(&mut self, due_date: Option<NaiveDateTime>) {
-        self.due_date = due_date;
-        self.touch();
+        let old = std::mem::replace(&mut self.due_date, due_date);
+        self.track("due_date", format_value(old), format_value(due_date));
+        for reminder in &mut self.reminders {
+            reminder.follow_due(due_date);
+        }
     }
 
     pub fn set_priority(&mut self, priority: Option<Priority>) {
-        self.priority = priority;
-        self.touch();
+        let old = std::mem::replace(&mut self.priority, priority);
+        self.track(
+            "priority",
+            old.map(|p| format!("{p:?}")),
+            priority.map(|p| format!("{p:?}")),
+        );
     }
 
     pub fn set_tags(&mut self, tags: Option<Vec<String>>) {
-        self.tags = tags;
+        let old = std::mem::replace(&mut self.tags, tags).map(|t| t.join(", "));
+        self.track("tags", old, self.tags_string());
+    }
+
+    pub fn set_parent_id(&mut self, parent_id: Option<u32>) {
+        let old = std::mem::replace(&mut self.parent_id, parent_id);
+        self.track(
+            "parent",
+            old.map(|id| id.to_string()),
+            parent_id.map(|id| id.to_string()),
+        );
+    }
+
+    pub fn set_depends_on(&mut self, depends_on: Vec<u32>) {
+        let join = |ids: &[u32]| {
+            (!ids.is_empty()).then(|| {
+                ids.iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+        };
+        let new = join(&depends_on);
+        let old = std::mem::replace(&mut self.depends_on, depends_on);
+        self.track("depends_on", join(&old), new);
+    }
+
+    pub fn set_scheduled(&mut self, scheduled: Option<NaiveDateTime>) {
+        let old = std::mem::replace(&mut self.scheduled, scheduled);
+        self.track("scheduled", format_value(old), format_value(scheduled));
+    }
+
+    pub fn set_wait_until(&mut self, wait_until: Option<NaiveDateTime>) {
+        let old = std::mem::replace(&mut self.wait_until, wait_until);
+        self.track("wait", format_value(old), format_value(wait_until));
+    }
+
+    pub fn set_estimate(&mut self, estimate: Option<Duration>) {
+        let minutes = estimate.map(|e| e.num_minutes());
+        let old = std::mem::replace(&mut self.estimate, minutes);
+        let format = |m: Option<i64>| m.and_then(Duration::try_minutes).map(format_duration);
+        self.track("estimate", format(old), format(minutes));
+    }
+
+    // Notes have their own timestamps, so they are not added to the history
+    pub fn add_note(&mut self, text: String) {
+        self.touch();
+        self.notes.push(Note::new(text, self.updated_at));
+    }
+
+    pub fn set_project(&mut self, project: Option<String>) {
+        let old = std::mem::replace(&mut self.project, project.clone());
+        self.track("project", old, project);
+    }
+
+    pub fn set_reminders(&mut self, reminders: Vec<Reminder>) {
+        let old = std::mem::replace(&mut self.reminders, reminders);
+        let new = format_reminders(&self.reminders);
+        self.track("reminders", format_reminders(&old), new);
+    }
+
+    pub fn add_reminder(&mut self, reminder: Reminder) {
+        self.reminders.push(reminder);
+        self.track("reminders", None, format_value(Some(reminder.at)));
+    }
+
+    // Mark the reminders that fired by `now` as shown and return them.
+    // Delivery is bookkeeping, so it is left out of the history.
+    pub fn deliver_reminders(&mut self, now: NaiveDateTime) -> Vec<Reminder> {
+        let mut delivered = Vec::new();
+        for reminder in self.reminders.iter_mut().filter(|r| r.is_pending(now)) {
+            reminder.delivered = true;
+            delivered.push(*reminder);
+        }
+        delivered
+    }
+
+    pub fn set_assignee(&mut self, assignee: Option<String>) {
+        let old = std::mem::replace(&mut self.assignee, assignee.clone());
+        self.track("assignee", old, assignee);
+    }
+
+    // `None` removes the attribute
+    pub fn set_uda(&mut self, key: &str, value: Option<String>) {
+        let old = match &value {
+            Some(value) => self.udas.insert(key.to_string(), value.clone()),
+            None => self.udas.remove(key),
+        };
+        self.track(key, old, value);
+    }
+
+    pub fn attach(&mut self, attachment: Attachment) {
+        self.track("attachments", None, Some(attachment.target.clone()));
+        self.attachments.push(attachment);
+    }
+
+    pub fn log_time(&mut self, entry: TimeEntry) {
+        self.time_log.push(entry);
+        self.track("time", None, Some(format_duration(entry.duration())));
+    }
+
+    pub fn set_recurrence(&mut self, recurrence: Option<Recurrence>) {
+        let old = std::mem::replace(&mut self.recurrence, recurrence);
+        self.track(
+            "repeat",
+            old.map(|r| r.to_string()),
+            recurrence.map(|r| r.to_string()),
+        );
+    }
+
+    pub fn set_deleted_at(&mut self, deleted_at: Option<NaiveDateTime>) {
+        let old = std::mem::replace(&mut self.deleted_at, deleted_at);
+        self.track("deleted_at", format_value(old), format_value(deleted_at));
+    }
+
+    // Bump `updated_at` and add a history entry if the value actually changed
+    fn track(&mut self, field: &str, old: Option<String>, new: Option<String>) {
         self.touch();
+        if old != new {
+            self.history
+                .push(HistoryEntry::new(field, old, new, self.updated_at));
+        }
     }
 
     fn touch(&mut self) {
@@ -149,11 +583,79 @@ impl fmt::Display for Priority {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Status {
+    #[default]
+    Pending,
+    InProgress,
+    Waiting,
+    Done,
+    Cancelled,
+}
+
+impl FromStr for Status {
+    type Err = TodoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['_', ' '], "-").as_str() {
+            "pending" | "todo" => Ok(Status::Pending),
+            "in-progress" | "inprogress" | "started" => Ok(Status::InProgress),
+            "waiting" => Ok(Status::Waiting),
+            "done" | "completed" => Ok(Status::Done),
+            "cancelled" | "canceled" => Ok(Status::Cancelled),
+            _ => Err(TodoError::InvalidStatus {
+                input: s.to_string(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Pending => write!(f, "pending"),
+            Status::InProgress => write!(f, "in progress"),
+            Status::Waiting => write!(f, "waiting"),
+            Status::Done => write!(f, "done"),
+            Status::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+// Accept both a `Status` and the legacy `completed` flag
+fn deserialize_status<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Status, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StatusOrCompleted {
+        Status(Status),
+        Completed(bool),
+    }
+
+    Ok(match StatusOrCompleted::deserialize(deserializer)? {
+        StatusOrCompleted::Status(status) => status,
+        StatusOrCompleted::Completed(true) => Status::Done,
+        StatusOrCompleted::Completed(false) => Status::Pending,
+    })
+}
+
+fn format_reminders(reminders: &[Reminder]) -> Option<String> {
+    (!reminders.is_empty()).then(|| {
+        reminders
+            .iter()
+            .filter_map(|r| format_value(Some(r.at)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Default)]
 pub enum SortBy {
+    #[default]
     Due,
     Priority,
     DueThenPriority,
+    // A user-defined attribute, written `uda:<name>`
+    Uda(String),
 }
 
 impl FromStr for SortBy {
@@ -164,9 +666,14 @@ impl FromStr for SortBy {
             "due" => Ok(SortBy::Due),
             "priority" => Ok(SortBy::Priority),
             "due+priority" => Ok(SortBy::DueThenPriority),
-            _ => Err(TodoError::InvalidSortField {
-                field: s.to_string(),
-            }),
+            _ => match s.split_once(':') {
+                Some((prefix, key)) if prefix.eq_ignore_ascii_case("uda") && !key.is_empty() => {
+                    Ok(SortBy::Uda(key.to_string()))
+                }
+                _ => Err(TodoError::InvalidSortField {
+                    field: s.to_string(),
+                }),
+            },
         }
     }
 }
@@ -223,6 +730,7 @@ mod tests {
         assert!(todo.due_date().is_none());
         assert_eq!(todo.priority(), None);
         assert_eq!(todo.tags(), None);
+        assert_ne!(todo.uuid(), create_minimal_todo().uuid());
     }
 
     #[test]
@@ -281,6 +789,194 @@ mod tests {
         assert!(!no_due_date.is_overdue());
     }
 
+    #[test]
+    fn test_setters_record_history() {
+        let mut todo = create_test_todo();
+        assert!(todo.history().is_empty());
+
+        todo.set_title("Renamed".to_string());
+        todo.set_priority(Some(Priority::Low));
+        todo.set_completed(true);
+        todo.set_description(None);
+        // Setting an unchanged value is not a change
+        todo.set_title("Renamed".to_string());
+
+        let history = todo.history();
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[0].field, "title");
+        assert_eq!(history[0].old.as_deref(), Some("Test Todo"));
+        assert_eq!(history[0].new.as_deref(), Some("Renamed"));
+        assert_eq!(history[1].old.as_deref(), Some("High"));
+        assert_eq!(history[1].new.as_deref(), Some("Low"));
+        assert_eq!(history[2].field, "status");
+        assert_eq!(history[2].new.as_deref(), Some("done"));
+        assert_eq!(history[3].new, None);
+        assert!(history[3].timestamp <= todo.updated_at());
+
+        let json = serde_json::to_string(&todo).unwrap();
+        let deserialized: TodoItem = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.history(), todo.history());
+    }
+
+    #[test]
+    fn test_completed_at() {
+        let mut todo = create_minimal_todo();
+        assert_eq!(todo.completed_at(), None);
+
+        todo.set_completed(true);
+        assert_eq!(todo.completed_at(), Some(todo.updated_at()));
+
+        // Completing again keeps the original timestamp
+        let completed_at = todo.completed_at();
+        todo.set_completed(true);
+        assert_eq!(todo.completed_at(), completed_at);
+
+        todo.set_completed(false);
+        assert_eq!(todo.completed_at(), None);
+
+        let done = TodoItem::new(2, "Done".to_string(), None, true, None, None, None);
+        assert_eq!(done.completed_at(), Some(done.created_at()));
+    }
+
+    #[test]
+    fn test_status_lifecycle() {
+        let mut todo = create_minimal_todo();
+        assert_eq!(todo.status(), Status::Pending);
+
+        todo.set_status(Status::InProgress);
+        assert!(!todo.is_closed());
+        assert_eq!(todo.completed_at(), None);
+
+        todo.set_status(Status::Cancelled);
+        assert!(todo.is_closed());
+        assert!(!todo.completed());
+        assert_eq!(todo.completed_at(), None);
+
+        todo.set_status(Status::Done);
+        assert!(todo.completed());
+        assert_eq!(todo.completed_at(), Some(todo.updated_at()));
+
+        todo.set_status(Status::Waiting);
+        assert_eq!(todo.completed_at(), None);
+    }
+
+    #[test]
+    fn test_legacy_completed_flag() {
+        let mut value = serde_json::to_value(create_minimal_todo()).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("status");
+        object.insert("completed".to_string(), serde_json::Value::Bool(true));
+
+        let todo: TodoItem = serde_json::from_value(value).unwrap();
+        assert_eq!(todo.status(), Status::Done);
+    }
+
+    #[test]
+    fn test_wait_until() {
+        let now = Local::now().naive_local();
+        let mut todo = create_minimal_todo();
+        assert!(!todo.is_waiting());
+
+        todo.set_wait_until(Some(now + chrono::Duration::days(3)));
+        assert!(todo.is_waiting());
+
+        // Closed todos are never hidden
+        todo.set_status(Status::Done);
+        assert!(!todo.is_waiting());
+
+        todo.set_status(Status::Pending);
+        todo.set_wait_until(Some(now - chrono::Duration::hours(1)));
+        assert!(!todo.is_waiting());
+    }
+
+    #[test]
+    fn test_next_occurrence_shifts_schedule() {
+        let due = NaiveDate::from_ymd_opt(2030, 3, 10)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap();
+        let day = chrono::Duration::days(1);
+        let todo = TodoItem::new(1, "Report".to_string(), None, false, Some(due), None, None)
+            .with_schedule(Some(due - day), Some(due - day * 3));
+
+        let next = todo.next_occurrence(2, Some(due + day * 7));
+        assert_eq!(next.scheduled(), Some(due + day * 6));
+        assert_eq!(next.wait_until(), Some(due + day * 4));
+    }
+
+    #[test]
+    fn test_time_tracking() {
+        let mut todo = create_minimal_todo();
+        assert_eq!(todo.time_spent(None), Duration::zero());
+
+        todo.set_estimate(Duration::try_hours(2));
+        assert_eq!(todo.estimate(), Duration::try_hours(2));
+
+        let start = NaiveDate::from_ymd_opt(2025, 3, 5)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap();
+        let hour = Duration::try_hours(1).unwrap();
+        todo.log_time(TimeEntry::new(start, start + hour));
+        todo.log_time(TimeEntry::new(start + hour * 24, start + hour * 26));
+
+        assert_eq!(todo.time_spent(None), hour * 3);
+        assert_eq!(todo.time_spent(Some(start + hour * 12)), hour * 2);
+        assert_eq!(
+            todo.history().last().unwrap().new.as_deref(),
+            Some("2h 00m")
+        );
+    }
+
+    #[test]
+    fn test_notes() {
+        let mut todo = create_minimal_todo();
+        todo.add_note("Called the client".to_string());
+        todo.add_note("Waiting for the PO number".to_string());
+
+        assert_eq!(todo.notes().len(), 2);
+        assert_eq!(todo.notes()[1].created_at, todo.updated_at());
+        assert!(todo.notes()[1].contains("po number"));
+        assert!(todo.history().is_empty());
+
+        let json = serde_json::to_string(&todo).unwrap();
+        let deserialized: TodoItem = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.notes(), todo.notes());
+    }
+
+    #[test]
+    fn test_is_blocked_by() {
+        let mut first = create_minimal_todo();
+        let mut second = TodoItem::new(2, "Second".to_string(), None, false, None, None, None);
+        second.set_depends_on(vec![1, 7]);
+        assert_eq!(second.depends_on(), &[1, 7]);
+
+        let todos = vec![first.clone(), second.clone()];
+        assert!(second.is_blocked_by(&todos));
+        assert!(!first.is_blocked_by(&todos));
+
+        first.set_completed(true);
+        assert!(!second.is_blocked_by(&[first.clone(), second.clone()]));
+
+        first.set_completed(false);
+        first.set_deleted_at(Some(Local::now().naive_local()));
+        assert!(!second.is_blocked_by(&[first, second.clone()]));
+    }
+
+    #[test]
+    fn test_trash_state() {
+        let mut todo = create_minimal_todo();
+        assert!(!todo.is_trashed());
+
+        let now = Local::now().naive_local();
+        todo.set_deleted_at(Some(now));
+        assert!(todo.is_trashed());
+        assert_eq!(todo.deleted_at(), Some(now));
+
+        todo.set_deleted_at(None);
+        assert!(!todo.is_trashed());
+    }
+
     #[test]
     fn test_priority_from_str() {
         assert_eq!("high".parse::<Priority>().unwrap(), Priority::High);
@@ -291,6 +987,20 @@ mod tests {
         assert!("".parse::<Priority>().is_err());
     }
 
+    #[test]
+    fn test_status_from_str() {
+        assert_eq!("pending".parse::<Status>().unwrap(), Status::Pending);
+        assert_eq!("in-progress".parse::<Status>().unwrap(), Status::InProgress);
+        assert_eq!("In_Progress".parse::<Status>().unwrap(), Status::InProgress);
+        assert_eq!("canceled".parse::<Status>().unwrap(), Status::Cancelled);
+        assert_eq!("done".parse::<Status>().unwrap(), Status::Done);
+
+        assert!(matches!(
+            "later".parse::<Status>(),
+            Err(TodoError::InvalidStatus { .. })
+        ));
+    }
+
     #[test]
     fn test_sort_by_from_str() {
         assert_eq!("due".parse::<SortBy>().unwrap(), SortBy::Due);
@@ -299,6 +1009,11 @@ mod tests {
             "due+priority".parse::<SortBy>().unwrap(),
             SortBy::DueThenPriority
         );
+        assert_eq!(
+            "uda:points".parse::<SortBy>().unwrap(),
+            SortBy::Uda("points".to_string())
+        );
+        assert!("uda:".parse::<SortBy>().is_err());
 
         let result: Result<SortBy, TodoError> = "invalid".parse();
         assert!(result.is_err());
@@ -319,6 +1034,7 @@ mod tests {
 
         let deserialized: TodoItem = serde_json::from_str(&json).expect("Should deserialize");
         assert_eq!(deserialized.id(), todo.id());
+        assert_eq!(deserialized.uuid(), todo.uuid());
         assert_eq!(deserialized.title(), todo.title());
         assert_eq!(deserialized.description(), todo.description());
         assert_eq!(deserialized.completed(), todo.completed());
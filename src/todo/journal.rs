@@ -0,0 +1,175 @@
+use crate::todo::TodoItem;
+use chrono::{Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+// Number of operations kept for undo; older entries are dropped
+pub const MAX_JOURNAL_ENTRIES: usize = 50;
+
+// State of a single todo before and after an operation. `None` means the
+// todo did not exist on that side (added or deleted).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Change {
+    pub before: Option<TodoItem>,
+    pub after: Option<TodoItem>,
+}
+
+impl Change {
+    pub fn id(&self) -> Option<u32> {
+        self.before.as_ref().or(self.after.as_ref()).map(|t| t.id())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub description: String,
+    pub timestamp: NaiveDateTime,
+    pub changes: Vec<Change>,
+}
+
+impl JournalEntry {
+    pub fn new(description: String, changes: Vec<Change>) -> Self {
+        JournalEntry {
+            description,
+            timestamp: Local::now().naive_local(),
+            changes,
+        }
+    }
+}
+
+// Undo and redo stacks of mutating operations, most recent last
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Journal {
+    undo: Vec<JournalEntry>,
+    redo: Vec<JournalEntry>,
+}
+
+impl Journal {
+    // Record a new operation. This invalidates anything that was undone.
+    pub fn record(&mut self, entry: JournalEntry) {
+        if entry.changes.is_empty() {
+            return;
+        }
+        self.undo.push(entry);
+        if self.undo.len() > MAX_JOURNAL_ENTRIES {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    pub fn pop_undo(&mut self) -> Option<JournalEntry> {
+        self.undo.pop()
+    }
+
+    pub fn pop_redo(&mut self) -> Option<JournalEntry> {
+        self.redo.pop()
+    }
+
+    pub fn push_undo(&mut self, entry: JournalEntry) {
+        self.undo.push(entry);
+    }
+
+    pub fn push_redo(&mut self, entry: JournalEntry) {
+        self.redo.push(entry);
+    }
+
+    // Drop every operation touching one of `ids`, e.g. once they leave the
+    // store, so undo and redo cannot bring them back
+    pub fn forget(&mut self, ids: &HashSet<u32>) {
+        let untouched = |entry: &JournalEntry| {
+            !entry
+                .changes
+                .iter()
+                .any(|c| c.id().is_some_and(|id| ids.contains(&id)))
+        };
+        self.undo.retain(untouched);
+        self.redo.retain(untouched);
+    }
+
+    pub fn undo_entries(&self) -> &[JournalEntry] {
+        &self.undo
+    }
+
+    pub fn redo_entries(&self) -> &[JournalEntry] {
+        &self.redo
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u32) -> JournalEntry {
+        let todo = TodoItem::new(id, format!("Task {id}"), None, false, None, None, None);
+        JournalEntry::new(
+            format!("add todo {id}"),
+            vec![Change {
+                before: None,
+                after: Some(todo),
+            }],
+        )
+    }
+
+    #[test]
+    fn test_change_id() {
+        let change = &entry(4).changes[0];
+        assert_eq!(change.id(), Some(4));
+    }
+
+    #[test]
+    fn test_record_clears_redo() {
+        let mut journal = Journal::default();
+        journal.record(entry(1));
+        let undone = journal.pop_undo().unwrap();
+        journal.push_redo(undone);
+        assert_eq!(journal.redo_entries().len(), 1);
+
+        journal.record(entry(2));
+        assert!(journal.redo_entries().is_empty());
+        assert_eq!(journal.undo_entries().len(), 1);
+    }
+
+    #[test]
+    fn test_record_ignores_empty_entries() {
+        let mut journal = Journal::default();
+        journal.record(JournalEntry::new("noop".to_string(), Vec::new()));
+        assert!(journal.undo_entries().is_empty());
+    }
+
+    #[test]
+    fn test_history_is_capped() {
+        let mut journal = Journal::default();
+        for id in 1..=(MAX_JOURNAL_ENTRIES as u32 + 5) {
+            journal.record(entry(id));
+        }
+
+        assert_eq!(journal.undo_entries().len(), MAX_JOURNAL_ENTRIES);
+        assert_eq!(journal.undo_entries()[0].description, "add todo 6");
+    }
+
+    #[test]
+    fn test_forget() {
+        let mut journal = Journal::default();
+        journal.record(entry(1));
+        journal.record(entry(2));
+        journal.record(entry(1));
+        let undone = journal.pop_undo().unwrap();
+        journal.push_redo(undone);
+
+        journal.forget(&HashSet::from([1]));
+        assert_eq!(journal.undo_entries().len(), 1);
+        assert_eq!(journal.undo_entries()[0].description, "add todo 2");
+        assert!(journal.redo_entries().is_empty());
+    }
+
+    #[test]
+    fn test_serialization_roundtrip() {
+        let mut journal = Journal::default();
+        journal.record(entry(1));
+
+        let json = serde_json::to_value(&journal).unwrap();
+        let restored: Journal = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.undo_entries().len(), 1);
+        assert_eq!(restored.undo_entries()[0].changes[0].id(), Some(1));
+    }
+}
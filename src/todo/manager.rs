@@ -1,53 +1,233 @@
-use crate::todo::{Priority, TodoError, TodoItem, TodoResult};
+use crate::config::Config;
+use crate::storage::{FileLock, JsonFileStore, TodoStore};
+use crate::todo::journal::{Change, Journal, JournalEntry};
+use crate::todo::{
+    format_duration, normalize_tags, parse_project, ActiveTimer, Attachment, HistoryEntry,
+    ListQuery, Priority, Recurrence, Reminder, Status, TimeEntry, TodoError, TodoFields, TodoItem,
+    TodoResult,
+};
 use crate::utils::validation::validate_id;
-use crate::utils::{parse_due_date, validate_text};
-use std::path::Path;
+use crate::utils::{parse_due_date, parse_duration, validate_text};
+use chrono::{Duration, Local, NaiveDateTime};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+pub const DEFAULT_LOCK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+const JOURNAL_KEY: &str = "journal";
+const TIMER_KEY: &str = "active_timer";
 
 pub struct TodoManager {
     pub todos: Vec<TodoItem>,
-    pub file_path: String,
     pub next_id: u32,
+    journal: Journal,
+    store: Box<dyn TodoStore>,
+    // Where completed todos are moved by `archive_completed`
+    archive: Option<Box<dyn TodoStore>>,
+    // Declares the user-defined attributes that can be set on todos
+    config: Config,
+    // Held from load until the manager is dropped
+    _lock: Option<FileLock>,
 }
 
 impl TodoManager {
     pub fn new(file_path: String) -> TodoResult<Self> {
-        let todos = Self::load_todos(&file_path)?;
-        let next_id = todos.iter().map(|t| t.id()).max().unwrap_or(0) + 1;
-        Ok(TodoManager {
-            todos,
-            file_path,
-            next_id,
-        })
+        Self::with_store(Box::new(JsonFileStore::new(file_path)))
     }
 
-    pub fn load_todos(file_path: &str) -> TodoResult<Vec<TodoItem>> {
-        if !Path::new(file_path).exists() {
-            return Ok(Vec::new());
+    pub fn with_store(store: Box<dyn TodoStore>) -> TodoResult<Self> {
+        Self::with_lock_timeout(store, DEFAULT_LOCK_TIMEOUT)
+    }
+
+    pub fn with_lock_timeout(
+        store: Box<dyn TodoStore>,
+        timeout: std::time::Duration,
+    ) -> TodoResult<Self> {
+        let lock = store
+            .lock_path()
+            .map(|path| FileLock::acquire(&path, timeout))
+            .transpose()?;
+
+        let mut manager = TodoManager {
+            todos: Vec::new(),
+            next_id: 1,
+            journal: Journal::default(),
+            store,
+            archive: None,
+            config: Config::default(),
+            _lock: lock,
+        };
+        manager.reload()?;
+        Ok(manager)
+    }
+
+    // Attach the store used by archive/unarchive and `--archived` listing.
+    // It is only guarded by the main store's lock.
+    pub fn with_archive(mut self, mut archive: Box<dyn TodoStore>) -> TodoResult<Self> {
+        archive.load()?;
+        self.archive = Some(archive);
+        Ok(self)
+    }
+
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn store(&self) -> &dyn TodoStore {
+        self.store.as_ref()
+    }
+
+    // Run `f` with its writes saved together when it succeeds, so a command's
+    // changes and its journal entry reach the store at once. On failure
+    // nothing is saved and the manager is reloaded from the store.
+    pub fn batch<T>(&mut self, f: impl FnOnce(&mut Self) -> TodoResult<T>) -> TodoResult<T> {
+        self.store.begin()?;
+        match f(self) {
+            Ok(value) => {
+                self.store.commit()?;
+                Ok(value)
+            }
+            Err(e) => {
+                self.store.rollback()?;
+                self.reload()?;
+                Err(e)
+            }
         }
+    }
 
-        let content = std::fs::read_to_string(file_path).map_err(|e| TodoError::FileError {
-            operation: "read".to_string(),
-            path: file_path.to_string(),
-            source: e,
-        })?;
+    // Read the todos, id counter and journal from the store
+    fn reload(&mut self) -> TodoResult<()> {
+        self.todos = self.store.load()?;
+        let max_id = self.todos.iter().map(|t| t.id()).max().unwrap_or(0);
+        self.next_id = self.store.next_id().max(max_id + 1);
+        self.journal = match self.store.meta(JOURNAL_KEY)? {
+            Some(value) => serde_json::from_value(value)?,
+            None => Journal::default(),
+        };
+        Ok(())
+    }
 
-        if content.trim().is_empty() {
-            return Ok(Vec::new());
+    pub fn save(&mut self) -> TodoResult<()> {
+        self.store.save(&self.todos)
+    }
+
+    pub fn query(&mut self, query: &ListQuery) -> TodoResult<Vec<TodoItem>> {
+        let mut todos = match query.archived {
+            true => self.archive_mut()?.query(query)?,
+            false => self.store.query(query)?,
+        };
+        if query.blocked || query.ready {
+            todos.retain(|t| !t.is_closed() && t.is_blocked_by(&self.todos) == query.blocked);
         }
+        Ok(todos)
+    }
 
-        serde_json::from_str(&content).map_err(TodoError::SerializationError)
+    fn archive_mut(&mut self) -> TodoResult<&mut Box<dyn TodoStore>> {
+        self.archive.as_mut().ok_or(TodoError::ArchiveUnavailable)
     }
 
-    pub fn save(&self) -> TodoResult<()> {
-        let content = serde_json::to_string(&self.todos)?;
-        std::fs::write(&self.file_path, content).map_err(|e| TodoError::FileError {
-            operation: "write".to_string(),
-            path: self.file_path.clone(),
-            source: e,
-        })?;
+    pub fn journal(&self) -> &Journal {
+        &self.journal
+    }
+
+    // Write the in-memory copy of a single todo back to the store
+    fn persist(&mut self, id: u32) -> TodoResult<()> {
+        let todo = self
+            .todos
+            .iter()
+            .find(|t| t.id() == id)
+            .ok_or(TodoError::TodoNotFound { id })?;
+        self.store.update(todo)
+    }
+
+    fn snapshot(&self, id: u32) -> Option<TodoItem> {
+        self.todos.iter().find(|t| t.id() == id).cloned()
+    }
+
+    // Add an operation to the undo journal and persist it
+    fn record(&mut self, description: String, changes: Vec<Change>) -> TodoResult<()> {
+        self.journal.record(JournalEntry::new(description, changes));
+        self.save_journal()
+    }
+
+    fn save_journal(&mut self) -> TodoResult<()> {
+        let value = serde_json::to_value(&self.journal)?;
+        self.store.set_meta(JOURNAL_KEY, value)
+    }
+
+    // Make the todo with `id` look like `state`, creating or removing it as needed
+    fn apply_state(&mut self, id: u32, state: Option<&TodoItem>) -> TodoResult<()> {
+        let position = self.todos.iter().position(|t| t.id() == id);
+        match (state, position) {
+            (Some(todo), Some(index)) => {
+                self.store.update(todo)?;
+                self.todos[index] = todo.clone();
+            }
+            (Some(todo), None) => {
+                self.store.insert(todo)?;
+                let index = self.todos.partition_point(|t| t.id() < id);
+                self.todos.insert(index, todo.clone());
+            }
+            (None, Some(index)) => {
+                self.store.delete(id)?;
+                self.todos.remove(index);
+            }
+            (None, None) => {}
+        }
         Ok(())
     }
 
+    pub fn undo(&mut self, steps: usize) -> TodoResult<usize> {
+        let mut undone = 0;
+        while undone < steps {
+            let Some(entry) = self.journal.pop_undo() else {
+                break;
+            };
+            for change in entry.changes.iter().rev() {
+                if let Some(id) = change.id() {
+                    self.apply_state(id, change.before.as_ref())?;
+                }
+            }
+            println!("↩️ Undid: {}", entry.description);
+            self.journal.push_redo(entry);
+            undone += 1;
+        }
+
+        if undone == 0 {
+            return Err(TodoError::NothingToUndo);
+        }
+        self.save_journal()?;
+        Ok(undone)
+    }
+
+    pub fn redo(&mut self, steps: usize) -> TodoResult<usize> {
+        let mut redone = 0;
+        while redone < steps {
+            let Some(entry) = self.journal.pop_redo() else {
+                break;
+            };
+            for change in &entry.changes {
+                if let Some(id) = change.id() {
+                    self.apply_state(id, change.after.as_ref())?;
+                }
+            }
+            println!("↪️ Redid: {}", entry.description);
+            self.journal.push_undo(entry);
+            redone += 1;
+        }
+
+        if redone == 0 {
+            return Err(TodoError::NothingToRedo);
+        }
+        self.save_journal()?;
+        Ok(redone)
+    }
+
     pub fn add_todo(
         &mut self,
         title: String,
@@ -56,12 +236,71 @@ impl TodoManager {
         priority: Option<&str>,
         tags: Option<Vec<String>>,
     ) -> TodoResult<()> {
-        let title = validate_text(title.as_str(), 140)?;
-        let description = description
+        self.add_todo_with(TodoFields {
+            title: Some(title),
+            description,
+            due_date,
+            priority: priority.map(str::to_string),
+            tags,
+            ..Default::default()
+        })?;
+        Ok(())
+    }
+
+    // Returns the id of the new todo
+    pub fn add_todo_with(&mut self, fields: TodoFields) -> TodoResult<u32> {
+        let title = validate_text(fields.title.as_deref().unwrap_or_default(), 140)?;
+        let description = fields
+            .description
             .map(|d| validate_text(d.as_str(), 1000))
             .transpose()?;
-        let parsed_due = parse_due_date(due_date.as_deref())?;
-        let parsed_priority = Self::parse_priority(priority)?;
+        let parsed_due = parse_due_date(fields.due_date.as_deref())?;
+        let scheduled = parse_due_date(fields.scheduled.as_deref())?;
+        let wait_until = parse_due_date(fields.wait.as_deref())?;
+        let parsed_priority = Self::parse_priority(fields.priority.as_deref())?;
+        let estimate = fields
+            .estimate
+            .as_deref()
+            .map(Self::parse_estimate)
+            .transpose()?
+            .flatten();
+        let recurrence = fields
+            .repeat
+            .as_deref()
+            .map(Self::parse_repeat)
+            .transpose()?
+            .flatten();
+        let udas = self
+            .parse_assignments(&fields.set)?
+            .into_iter()
+            .filter_map(|(key, value)| value.map(|v| (key, v)))
+            .collect();
+        let assignee = fields
+            .assignee
+            .as_deref()
+            .map(Self::parse_assignee)
+            .transpose()?
+            .flatten();
+        let reminders = fields
+            .remind
+            .iter()
+            .map(|r| Reminder::parse(r, parsed_due))
+            .collect::<TodoResult<Vec<_>>>()?;
+        let mut project = fields
+            .project
+            .as_deref()
+            .map(parse_project)
+            .transpose()?
+            .flatten();
+        if let Some(parent) = fields.parent {
+            let parent = self.find_live(parent)?;
+            if fields.project.is_none() {
+                project = parent.project().map(str::to_string);
+            }
+        }
+        if let Some(deps) = &fields.depends_on {
+            self.check_dependencies(self.next_id, deps)?;
+        }
         let next_id = self.next_id;
         self.next_id = next_id + 1;
 
@@ -72,14 +311,29 @@ impl TodoManager {
             false,
             parsed_due,
             parsed_priority,
-            tags,
-        );
-
+            fields.tags.and_then(normalize_tags),
+        )
+        .with_parent(fields.parent)
+        .with_dependencies(fields.depends_on.unwrap_or_default())
+        .with_recurrence(recurrence)
+        .with_schedule(scheduled, wait_until)
+        .with_estimate(estimate)
+        .with_project(project)
+        .with_assignee(assignee)
+        .with_reminders(reminders)
+        .with_udas(udas);
+
+        self.store.insert(&todo)?;
+        let mut changes = vec![Change {
+            before: None,
+            after: Some(todo.clone()),
+        }];
         self.todos.push(todo);
-        self.save()?;
+        changes.extend(self.reopen_ancestors(next_id)?);
+        self.record(format!("add todo {next_id}"), changes)?;
         println!("✅ Todo added with ID: {next_id}");
 
-        Ok(())
+        Ok(next_id)
     }
 
     pub fn parse_priority(priority_str: Option<&str>) -> TodoResult<Option<Priority>> {
@@ -91,6 +345,69 @@ impl TodoManager {
         }
     }
 
+    // "none" or "never" mean the todo does not repeat
+    pub fn parse_repeat(repeat: &str) -> TodoResult<Option<Recurrence>> {
+        match repeat.trim().to_lowercase().as_str() {
+            "none" | "never" => Ok(None),
+            _ => repeat.parse().map(Some),
+        }
+    }
+
+    // "none" means unassigned
+    pub fn parse_assignee(name: &str) -> TodoResult<Option<String>> {
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("none") {
+            return Ok(None);
+        }
+        if name.is_empty() || name.chars().any(char::is_whitespace) {
+            return Err(TodoError::InvalidAssignee {
+                name: name.to_string(),
+            });
+        }
+        Ok(Some(name.to_string()))
+    }
+
+    // The user `--mine` and `assign` refer to
+    pub fn current_user(&self) -> TodoResult<String> {
+        self.config.current_user().ok_or(TodoError::UnknownUser)
+    }
+
+    // Check `key=value` assignments against the declared attributes. An
+    // empty value yields `None`, which removes the attribute.
+    fn parse_assignments(&self, set: &[String]) -> TodoResult<Vec<(String, Option<String>)>> {
+        set.iter()
+            .map(|assignment| {
+                let (key, raw) =
+                    assignment
+                        .split_once('=')
+                        .ok_or_else(|| TodoError::InvalidAssignment {
+                            input: assignment.clone(),
+                        })?;
+                let key = key.trim();
+                let def = self
+                    .config
+                    .uda
+                    .get(key)
+                    .ok_or_else(|| TodoError::UnknownUda {
+                        key: key.to_string(),
+                    })?;
+                let value = match raw.trim() {
+                    "" => None,
+                    raw => Some(def.parse_value(key, raw)?),
+                };
+                Ok((key.to_string(), value))
+            })
+            .collect()
+    }
+
+    // "none" removes an estimate
+    pub fn parse_estimate(estimate: &str) -> TodoResult<Option<Duration>> {
+        match estimate.trim().to_lowercase().as_str() {
+            "none" => Ok(None),
+            _ => parse_duration(estimate).map(Some),
+        }
+    }
+
     pub fn edit_todo(
         &mut self,
         id: u32,
@@ -100,68 +417,646 @@ impl TodoManager {
         priority: Option<&str>,
         tags: Option<Vec<String>>,
     ) -> TodoResult<()> {
+        self.edit_todo_with(
+            id,
+            TodoFields {
+                title: new_title,
+                description: new_description,
+                due_date: due.map(str::to_string),
+                priority: priority.map(str::to_string),
+                tags,
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn edit_todo_with(&mut self, id: u32, fields: TodoFields) -> TodoResult<()> {
+        let before = self.snapshot(id);
+        if let Some(parent) = fields.parent {
+            self.find_live(parent)?;
+            if parent == id || self.descendant_ids(id).contains(&parent) {
+                return Err(TodoError::InvalidParent { id, parent });
+            }
+        }
+        if let Some(deps) = &fields.depends_on {
+            self.check_dependencies(id, deps)?;
+        }
+        let status = fields
+            .status
+            .as_deref()
+            .map(str::parse::<Status>)
+            .transpose()?;
+        let udas = self.parse_assignments(&fields.set)?;
+        let assignee = fields
+            .assignee
+            .as_deref()
+            .map(Self::parse_assignee)
+            .transpose()?;
         let todo = self.find_todo_mut(id)?;
 
-        if let Some(text) = new_title {
+        if let Some(text) = fields.title {
             todo.set_title(validate_text(&text, 140)?);
         }
-        if let Some(description) = new_description {
+        if let Some(description) = fields.description {
             todo.set_description(Some(validate_text(&description, 1000)?));
         }
-        if let Some(due_date) = due {
-            todo.set_due_date(parse_due_date(Some(due_date))?);
+        if let Some(due_date) = fields.due_date {
+            todo.set_due_date(parse_due_date(Some(&due_date))?);
+        }
+        if let Some(scheduled) = fields.scheduled {
+            todo.set_scheduled(parse_due_date(Some(&scheduled))?);
+        }
+        if let Some(wait) = fields.wait {
+            todo.set_wait_until(parse_due_date(Some(&wait))?);
+        }
+        if let Some(priority_str) = fields.priority {
+            todo.set_priority(Self::parse_priority(Some(&priority_str))?);
         }
-        if let Some(priority_str) = priority {
-            todo.set_priority(Self::parse_priority(Some(priority_str))?);
+        if let Some(estimate) = fields.estimate {
+            todo.set_estimate(Self::parse_estimate(&estimate)?);
         }
-        if let Some(tags) = tags {
-            todo.set_tags(Some(tags));
+        if let Some(tags) = fields.tags {
+            todo.set_tags(normalize_tags(tags));
+        }
+        if let Some(parent) = fields.parent {
+            todo.set_parent_id(Some(parent));
+        }
+        if let Some(deps) = fields.depends_on {
+            todo.set_depends_on(deps);
+        }
+        if let Some(repeat) = fields.repeat {
+            todo.set_recurrence(Self::parse_repeat(&repeat)?);
+        }
+        if let Some(assignee) = assignee {
+            todo.set_assignee(assignee);
+        }
+        if !fields.remind.is_empty() {
+            let reminders = match fields.remind.as_slice() {
+                [none] if none.trim().eq_ignore_ascii_case("none") => Vec::new(),
+                remind => remind
+                    .iter()
+                    .map(|r| Reminder::parse(r, todo.due_date()))
+                    .collect::<TodoResult<Vec<_>>>()?,
+            };
+            todo.set_reminders(reminders);
+        }
+        for (key, value) in udas {
+            todo.set_uda(&key, value);
         }
 
-        self.save()?;
+        self.persist(id)?;
+        let after = self.snapshot(id);
+        let mut changes = vec![Change { before, after }];
+        match status {
+            Some(status) => changes.extend(self.apply_status(id, status)?),
+            None => changes.extend(self.reopen_ancestors(id)?),
+        }
+        self.record(format!("edit todo {id}"), changes)?;
         println!("✏️ Todo {id} edited!");
         Ok(())
     }
 
+    // Only live todos can be found; trashed ones have to be restored first
     pub fn find_todo_mut(&mut self, id: u32) -> TodoResult<&mut TodoItem> {
         validate_id(&id.to_string())?;
         self.todos
             .iter_mut()
+            .find(|t| t.id() == id && !t.is_trashed())
+            .ok_or(TodoError::TodoNotFound { id })
+    }
+
+    // Look up any loaded todo, including trashed ones
+    pub fn find_todo(&self, id: u32) -> TodoResult<&TodoItem> {
+        self.todos
+            .iter()
             .find(|t| t.id() == id)
             .ok_or(TodoError::TodoNotFound { id })
     }
 
+    fn find_live(&self, id: u32) -> TodoResult<&TodoItem> {
+        self.todos
+            .iter()
+            .find(|t| t.id() == id && !t.is_trashed())
+            .ok_or(TodoError::TodoNotFound { id })
+    }
+
+    pub fn is_blocked(&self, id: u32) -> bool {
+        self.find_live(id)
+            .is_ok_and(|t| t.is_blocked_by(&self.todos))
+    }
+
+    // Every dependency must be a live todo, and `id` must not already be
+    // reachable from it, which would close a cycle
+    fn check_dependencies(&self, id: u32, deps: &[u32]) -> TodoResult<()> {
+        for &dependency in deps {
+            self.find_live(dependency)?;
+            let mut stack = vec![dependency];
+            let mut seen = Vec::new();
+            while let Some(current) = stack.pop() {
+                if current == id {
+                    return Err(TodoError::DependencyCycle { id, dependency });
+                }
+                if seen.contains(&current) {
+                    continue;
+                }
+                seen.push(current);
+                if let Some(todo) = self.snapshot(current) {
+                    stack.extend_from_slice(todo.depends_on());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Direct subtasks of a todo, excluding trashed ones
+    pub fn subtasks(&self, id: u32) -> Vec<&TodoItem> {
+        self.todos
+            .iter()
+            .filter(|t| t.parent_id() == Some(id) && !t.is_trashed())
+            .collect()
+    }
+
+    // Ids of every subtask below `id`, at any depth
+    fn descendant_ids(&self, id: u32) -> Vec<u32> {
+        let mut ids = Vec::new();
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            for child in self.todos.iter().filter(|t| t.parent_id() == Some(current)) {
+                if child.id() != id && !ids.contains(&child.id()) {
+                    ids.push(child.id());
+                    stack.push(child.id());
+                }
+            }
+        }
+        ids
+    }
+
+    fn ancestor_ids(&self, id: u32) -> Vec<u32> {
+        let mut ids = Vec::new();
+        let mut current = self.snapshot(id).and_then(|t| t.parent_id());
+        while let Some(parent) = current {
+            if parent == id || ids.contains(&parent) {
+                break;
+            }
+            ids.push(parent);
+            current = self.snapshot(parent).and_then(|t| t.parent_id());
+        }
+        ids
+    }
+
+    // Apply `f` to each of `ids`, persist them and return the journal changes
+    fn modify(&mut self, ids: &[u32], mut f: impl FnMut(&mut TodoItem)) -> TodoResult<Vec<Change>> {
+        let mut changes = Vec::new();
+        for &id in ids {
+            let before = self.snapshot(id);
+            if let Some(todo) = self.todos.iter_mut().find(|t| t.id() == id) {
+                f(todo);
+            }
+            self.persist(id)?;
+            let after = self.snapshot(id);
+            changes.push(Change { before, after });
+        }
+        Ok(changes)
+    }
+
+    // A closed todo cannot have open subtasks, so reopen the parents of an
+    // open todo
+    fn reopen_ancestors(&mut self, id: u32) -> TodoResult<Vec<Change>> {
+        if self.find_live(id)?.is_closed() {
+            return Ok(Vec::new());
+        }
+        let ids: Vec<u32> = self
+            .ancestor_ids(id)
+            .into_iter()
+            .filter(|a| self.find_live(*a).is_ok_and(|t| t.is_closed()))
+            .collect();
+        if !ids.is_empty() {
+            println!("↩️ Reopened {} parent todo(s)", ids.len());
+        }
+        self.modify(&ids, |t| t.set_status(Status::Pending))
+    }
+
+    pub fn history(&self, id: u32) -> TodoResult<&[HistoryEntry]> {
+        self.find_todo(id).map(|t| t.history())
+    }
+
+    pub fn find_by_uuid(&self, uuid: Uuid) -> Option<&TodoItem> {
+        self.todos.iter().find(|t| t.uuid() == uuid)
+    }
+
+    // Switch a todo between done and pending
     pub fn toggle_todo(&mut self, id: u32) -> TodoResult<()> {
-        let todo = self.find_todo_mut(id)?;
-        todo.set_completed(!todo.completed());
+        let status = match self.find_todo_mut(id)?.completed() {
+            true => Status::Pending,
+            false => Status::Done,
+        };
+        let changes = self.apply_status(id, status)?;
+        self.record(format!("toggle todo {id}"), changes)?;
+        Ok(())
+    }
 
-        if todo.completed() {
-            println!("✅ Todo {id} marked as completed!");
-        } else {
-            println!("✅ Todo {id} marked as pending!");
+    pub fn start_todo(&mut self, id: u32) -> TodoResult<()> {
+        self.set_status(id, Status::InProgress)
+    }
+
+    pub fn cancel_todo(&mut self, id: u32) -> TodoResult<()> {
+        self.set_status(id, Status::Cancelled)
+    }
+
+    pub fn set_status(&mut self, id: u32, status: Status) -> TodoResult<()> {
+        self.find_todo_mut(id)?;
+        let changes = self.apply_status(id, status)?;
+        self.record(format!("set todo {id} to {status}"), changes)?;
+        Ok(())
+    }
+
+    // Closing a todo also closes its open subtasks and, when it is done,
+    // spawns the next occurrence of a repeating todo. Reopening it reopens
+    // its closed parents.
+    fn apply_status(&mut self, id: u32, status: Status) -> TodoResult<Vec<Change>> {
+        let closing = matches!(status, Status::Done | Status::Cancelled);
+        let mut ids = vec![id];
+        if closing {
+            ids.extend(
+                self.descendant_ids(id)
+                    .into_iter()
+                    .filter(|d| self.find_live(*d).is_ok_and(|t| !t.is_closed())),
+            );
+        }
+        // Spawn the next occurrence first so a rule that cannot produce one
+        // leaves the todo untouched
+        let mut changes = Vec::new();
+        if status == Status::Done {
+            if let Some(rule) = self.find_live(id)?.recurrence() {
+                changes.extend(self.spawn_next_occurrence(id, rule)?);
+            }
+        }
+        changes.extend(self.modify(&ids, |t| t.set_status(status))?);
+
+        match status {
+            Status::Done => println!("✅ Todo {id} marked as completed!"),
+            Status::Pending => println!("✅ Todo {id} marked as pending!"),
+            Status::InProgress => println!("🔄 Todo {id} started!"),
+            Status::Waiting => println!("⏳ Todo {id} marked as waiting!"),
+            Status::Cancelled => println!("❌ Todo {id} cancelled!"),
+        }
+        match (status, ids.len() - 1) {
+            (_, 0) => {}
+            (Status::Done, n) => println!("✅ Also completed {n} subtask(s)"),
+            (_, n) => println!("❌ Also cancelled {n} subtask(s)"),
+        }
+
+        if !closing {
+            changes.extend(self.reopen_ancestors(id)?);
+        }
+        Ok(changes)
+    }
+
+    // Add the next instance of a repeating todo. The rule moves to the new
+    // todo, so reopening and completing the old one does not repeat it twice.
+    fn spawn_next_occurrence(&mut self, id: u32, rule: Recurrence) -> TodoResult<Vec<Change>> {
+        let now = Local::now().naive_local();
+        let current = self.find_live(id)?.clone();
+        let mut due = rule.next_after(current.due_date().unwrap_or(now))?;
+        // Skip occurrences that already passed when completing late
+        while due <= now {
+            due = rule.next_after(due)?;
+        }
+
+        let next_id = self.next_id;
+        self.next_id = next_id + 1;
+        let next = current.next_occurrence(next_id, Some(due));
+
+        let mut changes = self.modify(&[id], |t| t.set_recurrence(None))?;
+        self.store.insert(&next)?;
+        changes.push(Change {
+            before: None,
+            after: Some(next.clone()),
+        });
+        self.todos.push(next);
+        println!(
+            "🔁 Next occurrence added with ID: {next_id}, due {}",
+            due.format("%d-%m-%Y %H:%M")
+        );
+        Ok(changes)
+    }
+
+    pub fn add_note(&mut self, id: u32, text: &str) -> TodoResult<()> {
+        let text = validate_text(text, 1000)?;
+        self.find_live(id)?;
+        let changes = self.modify(&[id], |t| t.add_note(text.clone()))?;
+        self.record(format!("add note to todo {id}"), changes)?;
+        println!("📝 Note added to todo {id}");
+        Ok(())
+    }
+
+    // Link a URL or an existing local file to a todo
+    pub fn attach(&mut self, id: u32, target: &str) -> TodoResult<()> {
+        let attachment = Attachment::parse(target, Local::now().naive_local())?;
+        let todo = self.find_live(id)?;
+        if todo
+            .attachments()
+            .iter()
+            .any(|a| a.target == attachment.target)
+        {
+            println!("📎 Todo {id} already has that attachment");
+            return Ok(());
+        }
+        let changes = self.modify(&[id], |t| t.attach(attachment.clone()))?;
+        self.record(format!("attach to todo {id}"), changes)?;
+        println!("📎 Attached {} to todo {id}", attachment.target);
+        Ok(())
+    }
+
+    pub fn active_timer(&self) -> TodoResult<Option<ActiveTimer>> {
+        match self.store.meta(TIMER_KEY)? {
+            Some(value) => Ok(serde_json::from_value(value)?),
+            None => Ok(None),
+        }
+    }
+
+    // Only one timer runs at a time; it has to be stopped before another
+    // todo can be timed
+    pub fn start_timer(&mut self, id: u32) -> TodoResult<()> {
+        self.find_live(id)?;
+        if let Some(active) = self.active_timer()? {
+            return Err(TodoError::TimerRunning { id: active.id });
+        }
+        let timer = ActiveTimer {
+            id,
+            started_at: Local::now().naive_local(),
+        };
+        self.store
+            .set_meta(TIMER_KEY, serde_json::to_value(Some(timer))?)?;
+        println!("⏱️ Timer started for todo {id}");
+        Ok(())
+    }
+
+    // Log the running timer's time on its todo. The todo may have been
+    // trashed meanwhile; if it was purged the time is dropped.
+    pub fn stop_timer(&mut self) -> TodoResult<TimeEntry> {
+        let timer = self.active_timer()?.ok_or(TodoError::NoActiveTimer)?;
+        let entry = TimeEntry::new(timer.started_at, Local::now().naive_local());
+        let id = timer.id;
+
+        if self.find_todo(id).is_ok() {
+            let changes = self.modify(&[id], |t| t.log_time(entry))?;
+            self.record(format!("log time on todo {id}"), changes)?;
+        }
+        self.store.set_meta(TIMER_KEY, serde_json::Value::Null)?;
+        println!(
+            "⏹️ Timer stopped for todo {id}: {}",
+            format_duration(entry.duration())
+        );
+        Ok(entry)
+    }
+
+    // Remind about a todo again after `duration`. Reminders that already
+    // fired are dismissed.
+    pub fn snooze(&mut self, id: u32, duration: &str) -> TodoResult<NaiveDateTime> {
+        let now = Local::now().naive_local();
+        let at = now
+            .checked_add_signed(parse_duration(duration)?)
+            .ok_or_else(|| TodoError::DateOutOfRange {
+                input: duration.to_string(),
+            })?;
+        self.find_live(id)?;
+        let changes = self.modify(&[id], |t| {
+            t.deliver_reminders(now);
+            t.add_reminder(Reminder::at(at));
+        })?;
+
+        self.record(format!("snooze todo {id}"), changes)?;
+        println!("💤 Todo {id} snoozed until {}", at.format("%d-%m-%Y %H:%M"));
+        Ok(at)
+    }
+
+    // Reminders of open todos that have not been shown yet
+    pub fn upcoming_reminders(&self) -> Vec<(u32, Reminder)> {
+        self.todos
+            .iter()
+            .filter(|t| !t.is_trashed() && !t.is_closed())
+            .flat_map(|t| {
+                t.reminders()
+                    .iter()
+                    .filter(|r| !r.delivered)
+                    .map(move |r| (t.id(), *r))
+            })
+            .collect()
+    }
+
+    // Reminders of open todos that fired by `now` and were not shown yet,
+    // oldest first. They are marked as delivered so they are returned once.
+    pub fn deliver_reminders(&mut self, now: NaiveDateTime) -> TodoResult<Vec<(u32, Reminder)>> {
+        let ids: Vec<u32> = self
+            .todos
+            .iter()
+            .filter(|t| !t.is_trashed() && !t.is_closed())
+            .filter(|t| t.reminders().iter().any(|r| r.is_pending(now)))
+            .map(|t| t.id())
+            .collect();
+
+        let mut fired = Vec::new();
+        for id in ids {
+            let todo = self.find_todo_mut(id)?;
+            fired.extend(todo.deliver_reminders(now).into_iter().map(|r| (id, r)));
+            self.persist(id)?;
         }
+        fired.sort_by_key(|(_, r)| r.at);
+        Ok(fired)
+    }
 
-        self.save()?;
+    // Give a todo to `user`, or to the current user if none is given;
+    // "none" unassigns it
+    pub fn assign(&mut self, id: u32, user: Option<&str>) -> TodoResult<()> {
+        let assignee = match user {
+            Some(user) => Self::parse_assignee(user)?,
+            None => Some(self.current_user()?),
+        };
+        self.find_live(id)?;
+        let changes = self.modify(&[id], |t| t.set_assignee(assignee.clone()))?;
+
+        self.record(format!("assign todo {id}"), changes)?;
+        match &assignee {
+            Some(user) => println!("👤 Todo {id} assigned to {user}"),
+            None => println!("👤 Todo {id} unassigned"),
+        }
         Ok(())
     }
 
+    // Put a todo and its subtasks into another project; "none" takes them
+    // out of any project
+    pub fn move_todo(&mut self, id: u32, project: &str) -> TodoResult<()> {
+        let project = parse_project(project)?;
+        self.find_live(id)?;
+        let mut ids = vec![id];
+        ids.extend(
+            self.descendant_ids(id)
+                .into_iter()
+                .filter(|d| self.find_live(*d).is_ok()),
+        );
+        let changes = self.modify(&ids, |t| t.set_project(project.clone()))?;
+
+        self.record(format!("move todo {id}"), changes)?;
+        match &project {
+            Some(project) => println!("📁 Todo {id} moved to {project}"),
+            None => println!("📁 Todo {id} removed from its project"),
+        }
+        if ids.len() > 1 {
+            println!("📁 Also moved {} subtask(s)", ids.len() - 1);
+        }
+        Ok(())
+    }
+
+    // Move a todo and its subtasks to the trash; they can be brought back
+    // with `restore_todo`
     pub fn delete_todo(&mut self, id: u32) -> TodoResult<()> {
-        let original_len = self.todos.len();
-        self.todos.retain(|t| t.id() != id);
+        self.find_todo_mut(id)?;
+        let now = Local::now().naive_local();
+        let mut ids = vec![id];
+        ids.extend(
+            self.descendant_ids(id)
+                .into_iter()
+                .filter(|d| self.find_live(*d).is_ok()),
+        );
+        let changes = self.modify(&ids, |t| t.set_deleted_at(Some(now)))?;
 
-        if self.todos.len() < original_len {
-            self.save()?;
-            println!("🗑️ Todo {id} deleted!");
-            Ok(())
-        } else {
-            Err(TodoError::TodoNotFound { id })
+        self.record(format!("trash todo {id}"), changes)?;
+        println!("🗑️ Todo {id} moved to trash!");
+        if ids.len() > 1 {
+            println!("🗑️ Also trashed {} subtask(s)", ids.len() - 1);
         }
+        Ok(())
+    }
+
+    // Subtasks that were trashed together with the todo come back with it
+    pub fn restore_todo(&mut self, id: u32) -> TodoResult<()> {
+        let deleted_at = self
+            .todos
+            .iter()
+            .find(|t| t.id() == id)
+            .and_then(|t| t.deleted_at())
+            .ok_or(TodoError::NotInTrash { id })?;
+        let mut ids = vec![id];
+        ids.extend(self.descendant_ids(id).into_iter().filter(|d| {
+            self.find_todo(*d)
+                .is_ok_and(|t| t.deleted_at() == Some(deleted_at))
+        }));
+        let changes = self.modify(&ids, |t| t.set_deleted_at(None))?;
+
+        self.record(format!("restore todo {id}"), changes)?;
+        println!("♻️ Todo {id} restored!");
+        Ok(())
+    }
+
+    // Permanently remove trashed todos, optionally only those trashed
+    // longer than `older_than` ago
+    pub fn purge_trash(&mut self, older_than: Option<Duration>) -> TodoResult<usize> {
+        let older_than = older_than.unwrap_or_else(Duration::zero);
+        let cutoff = Local::now()
+            .naive_local()
+            .checked_sub_signed(older_than)
+            .ok_or_else(|| TodoError::InvalidDuration {
+                input: format!("{}d", older_than.num_days()),
+            })?;
+        let (purged, kept): (Vec<TodoItem>, Vec<TodoItem>) = self
+            .todos
+            .iter()
+            .cloned()
+            .partition(|t| t.deleted_at().is_some_and(|d| d <= cutoff));
+        let count = purged.len();
+
+        // One save for the whole batch, so the trash is never half purged
+        if count > 0 {
+            self.store.save(&kept)?;
+            self.todos = kept;
+        }
+        let changes = purged
+            .into_iter()
+            .map(|todo| Change {
+                before: Some(todo),
+                after: None,
+            })
+            .collect();
+        self.record(format!("purge {count} todo(s) from trash"), changes)?;
+
+        println!("🔥 Purged {count} todo(s) from trash!");
+        Ok(count)
+    }
+
+    // Move closed (done or cancelled) todos, finished before
+    // `completed_before` if given, out of the main store. Cancelled todos
+    // have no completion time and count as finished when last updated.
+    // Archiving is not recorded in the undo journal, so operations on the
+    // archived todos are dropped from it.
+    pub fn archive_completed(
+        &mut self,
+        completed_before: Option<NaiveDateTime>,
+    ) -> TodoResult<usize> {
+        let archive = self.archive.as_mut().ok_or(TodoError::ArchiveUnavailable)?;
+        let (done, kept): (Vec<TodoItem>, Vec<TodoItem>) =
+            self.todos.iter().cloned().partition(|t| {
+                let finished_at = t.completed_at().unwrap_or(t.updated_at());
+                t.is_closed()
+                    && !t.is_trashed()
+                    && completed_before.iter().all(|before| finished_at < *before)
+            });
+        let ids: HashSet<u32> = done.iter().map(|t| t.id()).collect();
+
+        // Save each store once. The archive is written first so a failure in
+        // between leaves the todos in both stores rather than in neither;
+        // archiving again then replaces the stale archived copies.
+        if !ids.is_empty() {
+            let mut archived = archive.load()?;
+            archived.retain(|t| !ids.contains(&t.id()));
+            archived.extend(done);
+            archived.sort_by_key(|t| t.id());
+            archive.save(&archived)?;
+            self.store.save(&kept)?;
+            self.todos = kept;
+            self.journal.forget(&ids);
+            self.save_journal()?;
+        }
+
+        println!("📦 Archived {} todo(s)!", ids.len());
+        Ok(ids.len())
+    }
+
+    pub fn unarchive(&mut self, id: u32) -> TodoResult<()> {
+        let archive = self.archive.as_mut().ok_or(TodoError::ArchiveUnavailable)?;
+        let todo = archive
+            .load()?
+            .into_iter()
+            .find(|t| t.id() == id)
+            .ok_or(TodoError::NotArchived { id })?;
+        if self.todos.iter().any(|t| t.id() == id) {
+            return Err(TodoError::AlreadyInList { id });
+        }
+
+        self.store.insert(&todo)?;
+        archive.delete(id)?;
+        let index = self.todos.partition_point(|t| t.id() < id);
+        self.todos.insert(index, todo);
+
+        println!("📤 Todo {id} unarchived!");
+        Ok(())
     }
 
     pub fn clear_all(&mut self) -> usize {
         let count = self.todos.len();
-        self.todos.clear();
-        if let Err(e) = self.save() {
+        let changes = self
+            .todos
+            .drain(..)
+            .map(|todo| Change {
+                before: Some(todo),
+                after: None,
+            })
+            .collect();
+        let result = self
+            .store
+            .save(&[])
+            .and_then(|_| self.record(format!("clear {count} todo(s)"), changes));
+        if let Err(e) = result {
             eprintln!("❌ Failed to clear todos: {e}");
         } else {
             println!("🗑️ Cleared {count} todo(s)!");
@@ -173,7 +1068,9 @@ impl TodoManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::{InMemoryStore, SqliteStore};
     use std::fs;
+    use std::path::Path;
     use tempfile::NamedTempFile;
 
     fn create_test_file() -> NamedTempFile {
@@ -209,7 +1106,7 @@ mod tests {
 
         assert_eq!(manager.todos.len(), 0);
         assert_eq!(manager.next_id, 1);
-        assert_eq!(manager.file_path, file_path);
+        assert_eq!(manager.store().location(), file_path);
     }
 
     #[test]
@@ -228,14 +1125,326 @@ mod tests {
     }
 
     #[test]
-    fn test_load_todos_empty_file() {
+    fn test_concurrent_manager_waits_for_lock() {
         let temp_file = create_test_file();
-        let file_path = temp_file.path().to_str().unwrap();
+        let file_path = temp_file.path().to_str().unwrap().to_string();
+
+        let mut first = TodoManager::new(file_path.clone()).unwrap();
+
+        let second = TodoManager::with_lock_timeout(
+            Box::new(JsonFileStore::new(file_path.clone())),
+            std::time::Duration::from_millis(100),
+        );
+        assert!(matches!(second, Err(TodoError::LockTimeout { .. })));
+
+        first
+            .add_todo("Held".to_string(), None, None, None, None)
+            .unwrap();
+        drop(first);
+
+        let second = TodoManager::new(file_path).unwrap();
+        assert_eq!(second.todos.len(), 1);
+    }
+
+    #[test]
+    fn test_deleted_ids_are_not_reused() {
+        let temp_file = create_test_file();
+        let file_path = temp_file.path().to_str().unwrap().to_string();
+        fs::write(&file_path, create_test_todos_json()).unwrap();
+
+        let mut manager = TodoManager::new(file_path.clone()).unwrap();
+        manager
+            .add_todo("Second".to_string(), None, None, None, None)
+            .unwrap();
+        manager.delete_todo(2).unwrap();
+        manager.purge_trash(None).unwrap();
+        drop(manager);
+
+        let mut manager = TodoManager::new(file_path).unwrap();
+        assert_eq!(manager.next_id, 3);
+        manager
+            .add_todo("Third".to_string(), None, None, None, None)
+            .unwrap();
+        assert_eq!(manager.todos[1].id(), 3);
+
+        let uuid = manager.todos[1].uuid();
+        assert_eq!(manager.find_by_uuid(uuid).map(|t| t.id()), Some(3));
+    }
+
+    #[test]
+    fn test_batch_saves_changes_and_journal_together() {
+        let temp_file = create_test_file();
+        let file_path = temp_file.path().to_str().unwrap().to_string();
+        let mut manager = TodoManager::new(file_path.clone()).unwrap();
+        manager
+            .add_todo("First".to_string(), None, None, None, None)
+            .unwrap();
+
+        manager
+            .batch(|m| m.add_todo("Second".to_string(), None, None, None, None))
+            .unwrap();
+        // The backup is the state after the previous command, journal included
+        let backup = JsonFileStore::load_file(&JsonFileStore::backup_path(&file_path)).unwrap();
+        assert_eq!(backup.todos.len(), 1);
+        let journal: Journal = serde_json::from_value(backup.meta[JOURNAL_KEY].clone()).unwrap();
+        assert_eq!(journal.undo_entries().len(), 1);
+        assert_eq!(JsonFileStore::load_todos(&file_path).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_failed_batch_saves_nothing() {
+        let temp_file = create_test_file();
+        let file_path = temp_file.path().to_str().unwrap().to_string();
+        let stores: Vec<Box<dyn TodoStore>> = vec![
+            Box::new(JsonFileStore::new(file_path.clone())),
+            Box::new(SqliteStore::open_in_memory().unwrap()),
+        ];
+        for store in stores {
+            let mut manager = TodoManager::with_store(store).unwrap();
+            manager
+                .add_todo("First".to_string(), None, None, None, None)
+                .unwrap();
+
+            let result = manager.batch(|m| {
+                m.add_todo("Second".to_string(), None, None, None, None)?;
+                m.toggle_todo(99)
+            });
+            assert!(matches!(result, Err(TodoError::TodoNotFound { id: 99 })));
+            assert_eq!(manager.todos.len(), 1);
+            assert_eq!(manager.next_id, 2);
+            assert_eq!(manager.journal().undo_entries().len(), 1);
+            assert_eq!(manager.store.load().unwrap().len(), 1);
+        }
+        assert_eq!(JsonFileStore::load_todos(&file_path).unwrap().len(), 1);
+    }
+
+    fn in_memory_manager() -> TodoManager {
+        TodoManager::with_store(Box::new(InMemoryStore::new())).unwrap()
+    }
+
+    #[test]
+    fn test_undo_redo_add_edit_delete() {
+        let mut manager = in_memory_manager();
+        manager
+            .add_todo("Original".to_string(), None, None, None, None)
+            .unwrap();
+        manager
+            .edit_todo(1, Some("Edited".to_string()), None, None, None, None)
+            .unwrap();
+        manager.toggle_todo(1).unwrap();
+        manager.delete_todo(1).unwrap();
+        assert!(manager.todos[0].is_trashed());
+
+        manager.undo(1).unwrap();
+        assert!(!manager.todos[0].is_trashed());
+        assert!(manager.todos[0].completed());
+
+        assert_eq!(manager.undo(2).unwrap(), 2);
+        assert_eq!(manager.todos[0].title(), "Original");
+        assert!(!manager.todos[0].completed());
+
+        manager.undo(1).unwrap();
+        assert!(manager.todos.is_empty());
+        assert!(matches!(manager.undo(1), Err(TodoError::NothingToUndo)));
+
+        assert_eq!(manager.redo(10).unwrap(), 4);
+        assert!(manager.todos[0].is_trashed());
+        assert!(matches!(manager.redo(1), Err(TodoError::NothingToRedo)));
+    }
+
+    #[test]
+    fn test_undo_clear_all() {
+        let mut manager = in_memory_manager();
+        manager
+            .add_todo("First".to_string(), None, None, None, None)
+            .unwrap();
+        manager
+            .add_todo("Second".to_string(), None, None, None, None)
+            .unwrap();
 
-        fs::write(file_path, "").unwrap();
+        manager.clear_all();
+        manager.undo(1).unwrap();
+
+        let ids: Vec<u32> = manager.todos.iter().map(|t| t.id()).collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_new_operation_discards_redo() {
+        let mut manager = in_memory_manager();
+        manager
+            .add_todo("First".to_string(), None, None, None, None)
+            .unwrap();
+        manager.undo(1).unwrap();
 
-        let todos = TodoManager::load_todos(file_path).unwrap();
-        assert_eq!(todos.len(), 0);
+        manager
+            .add_todo("Second".to_string(), None, None, None, None)
+            .unwrap();
+        assert!(matches!(manager.redo(1), Err(TodoError::NothingToRedo)));
+    }
+
+    #[test]
+    fn test_journal_survives_reload() {
+        let temp_file = create_test_file();
+        let file_path = temp_file.path().to_str().unwrap().to_string();
+        fs::write(&file_path, create_test_todos_json()).unwrap();
+
+        let mut manager = TodoManager::new(file_path.clone()).unwrap();
+        manager.delete_todo(1).unwrap();
+        drop(manager);
+
+        let mut manager = TodoManager::new(file_path.clone()).unwrap();
+        manager.undo(1).unwrap();
+        drop(manager);
+
+        let manager = TodoManager::new(file_path).unwrap();
+        assert_eq!(manager.todos.len(), 1);
+        assert_eq!(manager.todos[0].title(), "Test Todo");
+        assert_eq!(manager.journal().redo_entries().len(), 1);
+    }
+
+    #[test]
+    fn test_with_in_memory_store() {
+        let existing = TodoItem::new(3, "Existing".to_string(), None, false, None, None, None);
+        let store = InMemoryStore::with_todos(vec![existing]);
+        let mut manager = TodoManager::with_store(Box::new(store)).unwrap();
+
+        assert_eq!(manager.next_id, 4);
+        assert_eq!(manager.store().location(), ":memory:");
+
+        manager
+            .add_todo("In memory".to_string(), None, None, None, None)
+            .unwrap();
+        manager.toggle_todo(3).unwrap();
+        manager.delete_todo(4).unwrap();
+
+        assert_eq!(manager.todos.len(), 2);
+        assert!(manager.todos[0].completed());
+        assert!(manager.todos[1].is_trashed());
+    }
+
+    fn manager_with_archive() -> TodoManager {
+        in_memory_manager()
+            .with_archive(Box::new(InMemoryStore::new()))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_archive_and_unarchive() {
+        let mut manager = manager_with_archive();
+        for title in ["Done", "Open", "Trashed"] {
+            manager
+                .add_todo(title.to_string(), None, None, None, None)
+                .unwrap();
+        }
+        manager.toggle_todo(1).unwrap();
+        manager.toggle_todo(3).unwrap();
+        manager.delete_todo(3).unwrap();
+
+        assert_eq!(manager.archive_completed(None).unwrap(), 1);
+        let ids: Vec<u32> = manager.todos.iter().map(|t| t.id()).collect();
+        assert_eq!(ids, vec![2, 3]);
+
+        let archived_query = ListQuery {
+            archived: true,
+            ..Default::default()
+        };
+        let archived = manager.query(&archived_query).unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].title(), "Done");
+
+        manager.unarchive(1).unwrap();
+        assert_eq!(manager.todos[0].id(), 1);
+        assert!(manager.query(&archived_query).unwrap().is_empty());
+        assert!(matches!(
+            manager.unarchive(1),
+            Err(TodoError::NotArchived { id: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_undo_after_archive_does_not_duplicate() {
+        let mut manager = manager_with_archive();
+        for title in ["Done", "Open"] {
+            manager
+                .add_todo(title.to_string(), None, None, None, None)
+                .unwrap();
+        }
+        manager.toggle_todo(1).unwrap();
+        manager.archive_completed(None).unwrap();
+        assert_eq!(manager.journal().undo_entries().len(), 1);
+
+        // Only "add todo 2" is left to undo; todo 1 stays archived
+        manager.undo(5).unwrap();
+        assert!(manager.todos.is_empty());
+
+        manager.unarchive(1).unwrap();
+        let ids: Vec<u32> = manager
+            .store
+            .load()
+            .unwrap()
+            .iter()
+            .map(|t| t.id())
+            .collect();
+        assert_eq!(ids, vec![1]);
+    }
+
+    #[test]
+    fn test_unarchive_refuses_id_in_list() {
+        let mut manager = manager_with_archive();
+        manager
+            .add_todo("Done".to_string(), None, None, None, None)
+            .unwrap();
+        manager.toggle_todo(1).unwrap();
+        let done = manager.todos[0].clone();
+        manager.archive_completed(None).unwrap();
+        manager.store.insert(&done).unwrap();
+        manager.todos.push(done);
+
+        assert!(matches!(
+            manager.unarchive(1),
+            Err(TodoError::AlreadyInList { id: 1 })
+        ));
+        assert_eq!(manager.todos.len(), 1);
+    }
+
+    #[test]
+    fn test_archive_includes_cancelled() {
+        let mut manager = manager_with_archive();
+        for title in ["Dropped", "Open"] {
+            manager
+                .add_todo(title.to_string(), None, None, None, None)
+                .unwrap();
+        }
+        manager.cancel_todo(1).unwrap();
+
+        let long_ago = Local::now().naive_local() - Duration::try_days(1).unwrap();
+        assert_eq!(manager.archive_completed(Some(long_ago)).unwrap(), 0);
+        assert_eq!(manager.archive_completed(None).unwrap(), 1);
+        let ids: Vec<u32> = manager.todos.iter().map(|t| t.id()).collect();
+        assert_eq!(ids, vec![2]);
+    }
+
+    #[test]
+    fn test_archive_completed_before() {
+        let mut manager = manager_with_archive();
+        manager
+            .add_todo("Done".to_string(), None, None, None, None)
+            .unwrap();
+        manager.toggle_todo(1).unwrap();
+
+        let long_ago = Local::now().naive_local() - Duration::try_days(1).unwrap();
+        assert_eq!(manager.archive_completed(Some(long_ago)).unwrap(), 0);
+        assert_eq!(manager.todos.len(), 1);
+    }
+
+    #[test]
+    fn test_archive_requires_archive_store() {
+        let mut manager = in_memory_manager();
+        assert!(matches!(
+            manager.archive_completed(None),
+            Err(TodoError::ArchiveUnavailable)
+        ));
     }
 
     #[test]
@@ -360,7 +1569,698 @@ mod tests {
 
         manager.delete_todo(1).unwrap();
 
-        assert_eq!(manager.todos.len(), 0);
+        assert_eq!(manager.todos.len(), 1);
+        assert!(manager.todos[0].is_trashed());
+        assert!(matches!(
+            manager.toggle_todo(1),
+            Err(TodoError::TodoNotFound { id: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_restore_todo() {
+        let mut manager = in_memory_manager();
+        manager
+            .add_todo("Oops".to_string(), None, None, None, None)
+            .unwrap();
+
+        assert!(matches!(
+            manager.restore_todo(1),
+            Err(TodoError::NotInTrash { id: 1 })
+        ));
+
+        manager.delete_todo(1).unwrap();
+        manager.restore_todo(1).unwrap();
+        assert!(!manager.todos[0].is_trashed());
+        assert_eq!(manager.query(&ListQuery::default()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_purge_trash() {
+        let mut manager = in_memory_manager();
+        for title in ["Old", "Recent", "Live"] {
+            manager
+                .add_todo(title.to_string(), None, None, None, None)
+                .unwrap();
+        }
+        manager.delete_todo(1).unwrap();
+        manager.delete_todo(2).unwrap();
+        let long_ago = Local::now().naive_local() - Duration::try_days(40).unwrap();
+        manager.todos[0].set_deleted_at(Some(long_ago));
+        manager.save().unwrap();
+
+        let purged = manager
+            .purge_trash(Some(Duration::try_days(30).unwrap()))
+            .unwrap();
+        assert_eq!(purged, 1);
+        let ids: Vec<u32> = manager.todos.iter().map(|t| t.id()).collect();
+        assert_eq!(ids, vec![2, 3]);
+
+        assert_eq!(manager.purge_trash(None).unwrap(), 1);
+        assert_eq!(manager.todos.len(), 1);
+        let trash = ListQuery {
+            trashed: true,
+            ..Default::default()
+        };
+        assert!(manager.query(&trash).unwrap().is_empty());
+
+        manager.undo(1).unwrap();
+        assert_eq!(manager.todos.len(), 2);
+    }
+
+    #[test]
+    fn test_purge_trash_with_huge_age() {
+        let mut manager = in_memory_manager();
+        manager
+            .add_todo("Old".to_string(), None, None, None, None)
+            .unwrap();
+        manager.delete_todo(1).unwrap();
+
+        let forever = parse_duration("1000000000w").unwrap();
+        assert!(matches!(
+            manager.purge_trash(Some(forever)),
+            Err(TodoError::InvalidDuration { .. })
+        ));
+        assert_eq!(manager.todos.len(), 1);
+    }
+
+    #[test]
+    fn test_history_through_manager() {
+        let mut manager = in_memory_manager();
+        manager
+            .add_todo("Draft".to_string(), None, None, None, None)
+            .unwrap();
+        manager
+            .edit_todo(1, Some("Final".to_string()), None, None, Some("high"), None)
+            .unwrap();
+        manager.toggle_todo(1).unwrap();
+        manager.delete_todo(1).unwrap();
+
+        let fields: Vec<&str> = manager
+            .history(1)
+            .unwrap()
+            .iter()
+            .map(|h| h.field.as_str())
+            .collect();
+        assert_eq!(fields, vec!["title", "priority", "status", "deleted_at"]);
+        assert!(matches!(
+            manager.history(9),
+            Err(TodoError::TodoNotFound { id: 9 })
+        ));
+    }
+
+    fn add_child(manager: &mut TodoManager, title: &str, parent: Option<u32>) -> u32 {
+        manager
+            .add_todo_with(TodoFields {
+                title: Some(title.to_string()),
+                parent,
+                ..Default::default()
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_subtasks_follow_parent_toggle() {
+        let mut manager = in_memory_manager();
+        let epic = add_child(&mut manager, "Epic", None);
+        let task = add_child(&mut manager, "Task", Some(epic));
+        let step = add_child(&mut manager, "Step", Some(task));
+        assert_eq!(manager.subtasks(epic).len(), 1);
+        assert_eq!(manager.todos[step as usize - 1].parent_id(), Some(task));
+
+        manager.toggle_todo(epic).unwrap();
+        assert!(manager.todos.iter().all(|t| t.completed()));
+
+        // Reopening a subtask reopens everything above it
+        manager.toggle_todo(step).unwrap();
+        assert!(manager.todos.iter().all(|t| !t.completed()));
+
+        manager.toggle_todo(epic).unwrap();
+        add_child(&mut manager, "Late step", Some(task));
+        assert!(!manager.todos[0].completed());
+        assert!(!manager.todos[1].completed());
+
+        // One undo reverts the whole cascade
+        manager.undo(2).unwrap();
+        assert!(manager.todos.iter().all(|t| !t.completed()));
+    }
+
+    #[test]
+    fn test_status_changes() {
+        let mut manager = in_memory_manager();
+        let epic = add_child(&mut manager, "Epic", None);
+        let task = add_child(&mut manager, "Task", Some(epic));
+        let done = add_child(&mut manager, "Done", Some(epic));
+        manager.toggle_todo(done).unwrap();
+
+        manager.start_todo(task).unwrap();
+        assert_eq!(
+            manager.find_todo(task).unwrap().status(),
+            Status::InProgress
+        );
+
+        // Cancelling closes open subtasks but leaves finished ones alone
+        manager.cancel_todo(epic).unwrap();
+        assert_eq!(manager.find_todo(task).unwrap().status(), Status::Cancelled);
+        assert_eq!(manager.find_todo(done).unwrap().status(), Status::Done);
+
+        let waiting = TodoFields {
+            status: Some("waiting".to_string()),
+            ..Default::default()
+        };
+        manager.edit_todo_with(task, waiting).unwrap();
+        assert_eq!(manager.find_todo(epic).unwrap().status(), Status::Pending);
+
+        let invalid = TodoFields {
+            status: Some("someday".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            manager.edit_todo_with(task, invalid),
+            Err(TodoError::InvalidStatus { .. })
+        ));
+
+        manager.undo(1).unwrap();
+        assert_eq!(manager.find_todo(task).unwrap().status(), Status::Cancelled);
+        assert_eq!(manager.find_todo(epic).unwrap().status(), Status::Cancelled);
+    }
+
+    #[test]
+    fn test_subtasks_follow_parent_delete_and_restore() {
+        let mut manager = in_memory_manager();
+        let epic = add_child(&mut manager, "Epic", None);
+        let task = add_child(&mut manager, "Task", Some(epic));
+        let earlier = add_child(&mut manager, "Earlier", Some(epic));
+        manager.delete_todo(earlier).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(1));
+
+        manager.delete_todo(epic).unwrap();
+        assert!(manager.todos.iter().all(|t| t.is_trashed()));
+        assert!(matches!(
+            manager.add_todo_with(TodoFields {
+                title: Some("Orphan".to_string()),
+                parent: Some(epic),
+                ..Default::default()
+            }),
+            Err(TodoError::TodoNotFound { .. })
+        ));
+
+        manager.restore_todo(epic).unwrap();
+        assert!(!manager.find_todo(task).unwrap().is_trashed());
+        assert!(manager.find_todo(earlier).unwrap().is_trashed());
+    }
+
+    #[test]
+    fn test_edit_parent_rejects_cycles() {
+        let mut manager = in_memory_manager();
+        let epic = add_child(&mut manager, "Epic", None);
+        let task = add_child(&mut manager, "Task", Some(epic));
+        let other = add_child(&mut manager, "Other", None);
+
+        let move_under = |parent| TodoFields {
+            parent: Some(parent),
+            ..Default::default()
+        };
+        assert!(matches!(
+            manager.edit_todo_with(epic, move_under(task)),
+            Err(TodoError::InvalidParent { id, parent }) if id == epic && parent == task
+        ));
+        assert!(manager.edit_todo_with(epic, move_under(epic)).is_err());
+
+        manager.edit_todo_with(task, move_under(other)).unwrap();
+        assert_eq!(manager.subtasks(other)[0].id(), task);
+        assert!(manager.subtasks(epic).is_empty());
+    }
+
+    fn add_dependent(manager: &mut TodoManager, title: &str, depends_on: Vec<u32>) -> u32 {
+        manager
+            .add_todo_with(TodoFields {
+                title: Some(title.to_string()),
+                depends_on: Some(depends_on),
+                ..Default::default()
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_dependencies_block_until_done() {
+        let mut manager = in_memory_manager();
+        let design = add_dependent(&mut manager, "Design", vec![]);
+        let build = add_dependent(&mut manager, "Build", vec![design]);
+        let ship = add_dependent(&mut manager, "Ship", vec![design, build]);
+        assert!(manager.is_blocked(build));
+
+        let ids = |manager: &mut TodoManager, query: ListQuery| -> Vec<u32> {
+            manager
+                .query(&query)
+                .unwrap()
+                .iter()
+                .map(|t| t.id())
+                .collect()
+        };
+        let blocked = || ListQuery {
+            blocked: true,
+            ..Default::default()
+        };
+        let ready = || ListQuery {
+            ready: true,
+            ..Default::default()
+        };
+        assert_eq!(ids(&mut manager, blocked()), vec![build, ship]);
+        assert_eq!(ids(&mut manager, ready()), vec![design]);
+
+        manager.toggle_todo(design).unwrap();
+        assert_eq!(ids(&mut manager, blocked()), vec![ship]);
+        assert_eq!(ids(&mut manager, ready()), vec![build]);
+    }
+
+    #[test]
+    fn test_dependency_cycles_are_rejected() {
+        let mut manager = in_memory_manager();
+        let a = add_dependent(&mut manager, "A", vec![]);
+        let b = add_dependent(&mut manager, "B", vec![a]);
+        let c = add_dependent(&mut manager, "C", vec![b]);
+
+        let depend_on = |deps: Vec<u32>| TodoFields {
+            depends_on: Some(deps),
+            ..Default::default()
+        };
+        assert!(matches!(
+            manager.edit_todo_with(a, depend_on(vec![c])),
+            Err(TodoError::DependencyCycle { id, dependency }) if id == a && dependency == c
+        ));
+        assert!(manager.edit_todo_with(a, depend_on(vec![a])).is_err());
+        assert!(matches!(
+            manager.edit_todo_with(a, depend_on(vec![99])),
+            Err(TodoError::TodoNotFound { id: 99 })
+        ));
+
+        manager.edit_todo_with(c, depend_on(vec![a])).unwrap();
+        assert_eq!(manager.find_todo(c).unwrap().depends_on(), &[a]);
+    }
+
+    #[test]
+    fn test_waiting_todos_are_hidden() {
+        let mut manager = in_memory_manager();
+        manager
+            .add_todo("Now".to_string(), None, None, None, None)
+            .unwrap();
+        let later = manager
+            .add_todo_with(TodoFields {
+                title: Some("Later".to_string()),
+                scheduled: Some(Local::now().format("%Y-%m-%d").to_string()),
+                wait: Some("next week".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let listed = manager.query(&ListQuery::default()).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].title(), "Now");
+
+        let waiting = ListQuery {
+            waiting: true,
+            ..Default::default()
+        };
+        let listed = manager.query(&waiting).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id(), later);
+
+        let cleared = TodoFields {
+            wait: Some(String::new()),
+            ..Default::default()
+        };
+        manager.edit_todo_with(later, cleared).unwrap();
+        assert_eq!(manager.query(&ListQuery::default()).unwrap().len(), 2);
+
+        let scheduled = ListQuery {
+            scheduled_today: true,
+            ..Default::default()
+        };
+        assert_eq!(manager.query(&scheduled).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_notes_are_appended_and_filterable() {
+        let mut manager = in_memory_manager();
+        let id = add_child(&mut manager, "Invoice", None);
+        add_child(&mut manager, "Other", None);
+
+        manager.add_note(id, "Sent to accounting").unwrap();
+        manager.add_note(id, "Paid on Friday").unwrap();
+        assert!(matches!(
+            manager.add_note(id, "   "),
+            Err(TodoError::EmptyTodo)
+        ));
+        assert!(manager.add_note(9, "Nope").is_err());
+        assert_eq!(manager.todos[0].notes().len(), 2);
+
+        let query = ListQuery {
+            note_contains: Some("ACCOUNTING".to_string()),
+            ..Default::default()
+        };
+        let found = manager.query(&query).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id(), id);
+
+        manager.undo(1).unwrap();
+        assert_eq!(manager.todos[0].notes().len(), 1);
+    }
+
+    #[test]
+    fn test_attach() {
+        let mut manager = in_memory_manager();
+        let id = add_child(&mut manager, "Fix login", None);
+
+        manager.attach(id, "https://example.com/issues/7").unwrap();
+        manager.attach(id, "https://example.com/issues/7").unwrap();
+        assert!(matches!(
+            manager.attach(id, "missing/notes.txt"),
+            Err(TodoError::AttachmentNotFound { .. })
+        ));
+        assert_eq!(manager.todos[0].attachments().len(), 1);
+        assert_eq!(manager.journal().undo_entries().len(), 2);
+    }
+
+    #[test]
+    fn test_tags_are_normalized() {
+        let mut manager = in_memory_manager();
+        let tags = |tags: &[&str]| Some(tags.iter().map(|t| t.to_string()).collect());
+        manager
+            .add_todo(
+                "Deploy".to_string(),
+                None,
+                None,
+                None,
+                tags(&[" Work", "work", "OPS"]),
+            )
+            .unwrap();
+        assert_eq!(
+            manager.todos[0].tags(),
+            Some(&["work".to_string(), "ops".to_string()][..])
+        );
+
+        let edit = TodoFields {
+            tags: tags(&[" "]),
+            ..Default::default()
+        };
+        manager.edit_todo_with(1, edit).unwrap();
+        assert_eq!(manager.todos[0].tags(), None);
+    }
+
+    #[test]
+    fn test_reminders() {
+        let mut manager = in_memory_manager();
+        let id = manager
+            .add_todo_with(TodoFields {
+                title: Some("Dentist".to_string()),
+                due_date: Some("next week".to_string()),
+                remind: vec!["1d before".to_string(), "01-01-2099 09:00".to_string()],
+                ..Default::default()
+            })
+            .unwrap();
+        let due = manager.find_todo(id).unwrap().due_date().unwrap();
+        assert_eq!(
+            manager.find_todo(id).unwrap().reminders()[0].at,
+            due - Duration::days(1)
+        );
+
+        // Nothing fires before the reminder time, and each reminder fires once
+        assert!(manager
+            .deliver_reminders(due - Duration::days(2))
+            .unwrap()
+            .is_empty());
+        let fired = manager.deliver_reminders(due).unwrap();
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].0, id);
+        assert!(manager.deliver_reminders(due).unwrap().is_empty());
+
+        let at = manager.snooze(id, "2h").unwrap();
+        assert!(manager.deliver_reminders(at).unwrap().len() == 1);
+        manager.toggle_todo(id).unwrap();
+        assert!(manager
+            .deliver_reminders(NaiveDateTime::MAX)
+            .unwrap()
+            .is_empty());
+
+        let edit = |remind: &str| TodoFields {
+            remind: vec![remind.to_string()],
+            ..Default::default()
+        };
+        manager.edit_todo_with(id, edit("none")).unwrap();
+        assert!(manager.find_todo(id).unwrap().reminders().is_empty());
+        let undated = add_child(&mut manager, "Someday", None);
+        assert!(matches!(
+            manager.edit_todo_with(undated, edit("1h before")),
+            Err(TodoError::ReminderWithoutDue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_reminder_durations_past_date_range() {
+        let mut manager = in_memory_manager();
+        let id = manager
+            .add_todo_with(TodoFields {
+                title: Some("Dentist".to_string()),
+                due_date: Some("tomorrow".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert!(matches!(
+            manager.snooze(id, "1000000000w"),
+            Err(TodoError::DateOutOfRange { .. })
+        ));
+        let far_reminder = TodoFields {
+            title: Some("Checkup".to_string()),
+            due_date: Some("tomorrow".to_string()),
+            remind: vec!["1000000000w before".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            manager.add_todo_with(far_reminder),
+            Err(TodoError::DateOutOfRange { .. })
+        ));
+        assert_eq!(manager.todos.len(), 1);
+        assert!(manager.todos[0].reminders().is_empty());
+    }
+
+    #[test]
+    fn test_assignees() {
+        let config: Config = toml::from_str("user = \"alice\"").unwrap();
+        let mut manager = in_memory_manager().with_config(config);
+        let mine = manager
+            .add_todo_with(TodoFields {
+                title: Some("Review".to_string()),
+                assignee: Some("alice".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        let other = add_child(&mut manager, "Deploy", None);
+
+        manager.assign(other, Some("bob")).unwrap();
+        assert_eq!(manager.find_todo(other).unwrap().assignee(), Some("bob"));
+        let query = ListQuery {
+            assignee: Some(manager.current_user().unwrap()),
+            ..Default::default()
+        };
+        let listed = manager.query(&query).unwrap();
+        assert_eq!(
+            listed.iter().map(|t| t.id()).collect::<Vec<_>>(),
+            vec![mine]
+        );
+
+        manager.assign(other, None).unwrap();
+        assert_eq!(manager.find_todo(other).unwrap().assignee(), Some("alice"));
+        manager.assign(other, Some("none")).unwrap();
+        assert_eq!(manager.find_todo(other).unwrap().assignee(), None);
+        assert!(matches!(
+            manager.assign(other, Some("bob smith")),
+            Err(TodoError::InvalidAssignee { .. })
+        ));
+    }
+
+    #[test]
+    fn test_projects() {
+        let mut manager = in_memory_manager();
+        let epic = manager
+            .add_todo_with(TodoFields {
+                title: Some("Epic".to_string()),
+                project: Some("Work.Backend".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        let task = add_child(&mut manager, "Task", Some(epic));
+        assert_eq!(
+            manager.find_todo(task).unwrap().project(),
+            Some("work.backend")
+        );
+
+        let query = ListQuery {
+            project: Some("work".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(manager.query(&query).unwrap().len(), 2);
+
+        manager.move_todo(epic, "home").unwrap();
+        assert_eq!(manager.find_todo(task).unwrap().project(), Some("home"));
+        assert!(manager.query(&query).unwrap().is_empty());
+        manager.undo(1).unwrap();
+        assert_eq!(manager.query(&query).unwrap().len(), 2);
+
+        manager.move_todo(task, "none").unwrap();
+        assert_eq!(manager.find_todo(task).unwrap().project(), None);
+        assert!(matches!(
+            manager.move_todo(epic, "my work"),
+            Err(TodoError::InvalidProject { .. })
+        ));
+    }
+
+    #[test]
+    fn test_user_defined_attributes() {
+        let config: Config = toml::from_str(
+            "[uda.points]\ntype = \"number\"\n[uda.size]\ntype = \"enum\"\nvalues = [\"S\", \"L\"]",
+        )
+        .unwrap();
+        let mut manager = in_memory_manager().with_config(config);
+        let id = manager
+            .add_todo_with(TodoFields {
+                title: Some("Story".to_string()),
+                set: vec!["points=3".to_string(), "size=l".to_string()],
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(manager.todos[0].uda("size"), Some("L"));
+
+        let edit = |set: &str| TodoFields {
+            set: vec![set.to_string()],
+            ..Default::default()
+        };
+        manager.edit_todo_with(id, edit("points=")).unwrap();
+        assert_eq!(manager.todos[0].uda("points"), None);
+        assert_eq!(manager.history(id).unwrap()[0].field, "points");
+
+        for (set, expected) in [
+            ("owner=me", "UnknownUda"),
+            ("size=XL", "InvalidUdaValue"),
+            ("size", "InvalidAssignment"),
+        ] {
+            let err = manager.edit_todo_with(id, edit(set)).unwrap_err();
+            assert!(format!("{err:?}").starts_with(expected), "{set}: {err:?}");
+        }
+    }
+
+    #[test]
+    fn test_timer_start_and_stop() {
+        let mut manager = in_memory_manager();
+        let id = manager
+            .add_todo_with(TodoFields {
+                title: Some("Invoice".to_string()),
+                estimate: Some("90m".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        let other = add_child(&mut manager, "Other", None);
+        assert_eq!(manager.todos[0].estimate(), Duration::try_minutes(90));
+
+        assert!(matches!(
+            manager.stop_timer(),
+            Err(TodoError::NoActiveTimer)
+        ));
+        manager.start_timer(id).unwrap();
+        assert_eq!(manager.active_timer().unwrap().unwrap().id, id);
+        assert!(matches!(
+            manager.start_timer(other),
+            Err(TodoError::TimerRunning { id: 1 })
+        ));
+
+        let entry = manager.stop_timer().unwrap();
+        assert_eq!(manager.active_timer().unwrap(), None);
+        assert_eq!(manager.todos[0].time_log(), &[entry]);
+
+        manager.undo(1).unwrap();
+        assert!(manager.todos[0].time_log().is_empty());
+    }
+
+    #[test]
+    fn test_completing_repeating_todo_spawns_next() {
+        let mut manager = in_memory_manager();
+        let due = Local::now().naive_local() + Duration::try_hours(1).unwrap();
+        let id = manager
+            .add_todo_with(TodoFields {
+                title: Some("Water plants".to_string()),
+                due_date: Some(due.format("%Y-%m-%d %H:%M").to_string()),
+                priority: Some("low".to_string()),
+                repeat: Some("every 2 days".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        manager.toggle_todo(id).unwrap();
+        assert_eq!(manager.todos.len(), 2);
+        let (done, next) = (&manager.todos[0], &manager.todos[1]);
+        assert!(done.completed());
+        assert_eq!(done.recurrence(), None);
+        assert!(!next.completed());
+        assert_eq!(next.title(), "Water plants");
+        assert_eq!(next.priority(), Some(Priority::Low));
+        assert_eq!(next.recurrence(), Some("every 2 days".parse().unwrap()));
+        let expected = done.due_date().unwrap() + Duration::try_days(2).unwrap();
+        assert_eq!(next.due_date(), Some(expected));
+
+        // Reopening the finished one does not repeat it again
+        manager.toggle_todo(id).unwrap();
+        manager.toggle_todo(id).unwrap();
+        assert_eq!(manager.todos.len(), 2);
+
+        manager.undo(3).unwrap();
+        assert_eq!(manager.todos.len(), 1);
+        assert!(manager.todos[0].recurrence().is_some());
+    }
+
+    #[test]
+    fn test_repeat_past_supported_dates_keeps_todo_open() {
+        let mut manager = in_memory_manager();
+        let id = manager
+            .add_todo_with(TodoFields {
+                title: Some("Someday".to_string()),
+                repeat: Some("every 100000000 days".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert!(matches!(
+            manager.toggle_todo(id),
+            Err(TodoError::RecurrenceOutOfRange { .. })
+        ));
+        assert_eq!(manager.todos.len(), 1);
+        assert!(!manager.todos[0].completed());
+        assert!(manager.todos[0].recurrence().is_some());
+    }
+
+    #[test]
+    fn test_late_completion_skips_missed_occurrences() {
+        let mut manager = in_memory_manager();
+        let id = manager
+            .add_todo_with(TodoFields {
+                title: Some("Standup".to_string()),
+                due_date: Some("2020-01-06 09:00".to_string()),
+                repeat: Some("daily".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        manager.toggle_todo(id).unwrap();
+        let next_due = manager.todos[1].due_date().unwrap();
+        assert!(next_due > Local::now().naive_local());
+        assert_eq!(next_due.time(), manager.todos[0].due_date().unwrap().time());
+
+        let fields = TodoFields {
+            repeat: Some("never".to_string()),
+            ..Default::default()
+        };
+        manager.edit_todo_with(2, fields).unwrap();
+        assert_eq!(manager.todos[1].recurrence(), None);
     }
 
     #[test]
@@ -1,9 +1,33 @@
+pub mod attachment;
 pub mod error;
+pub mod fields;
 pub mod filters;
+pub mod history;
 pub mod item;
+pub mod journal;
 pub mod manager;
+pub mod note;
+pub mod project;
+pub mod query;
+pub mod recurrence;
+pub mod reminder;
+pub mod tag;
+pub mod time_log;
+pub mod uda;
 
+pub use attachment::Attachment;
 pub use error::{TodoError, TodoResult};
+pub use fields::TodoFields;
 pub use filters::ListQuery;
-pub use item::{Priority, SortBy, TodoItem};
-pub use manager::TodoManager;
+pub use history::HistoryEntry;
+pub use item::{Priority, SortBy, Status, TodoItem};
+pub use journal::{Change, Journal, JournalEntry};
+pub use manager::{TodoManager, DEFAULT_LOCK_TIMEOUT};
+pub use note::Note;
+pub use project::{in_project, parse_project, project_counts, ProjectCount};
+pub use query::{Condition, DateField, FilterExpr, TextField};
+pub use recurrence::{Frequency, Recurrence};
+pub use reminder::Reminder;
+pub use tag::{has_tag, normalize_tag, normalize_tags, tag_counts, TagCount};
+pub use time_log::{format_duration, ActiveTimer, TimeEntry};
+pub use uda::{Comparison, UdaDef, UdaFilter, UdaType, Udas};
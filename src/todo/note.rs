@@ -0,0 +1,20 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+// A comment appended to a todo with `todo note`; notes are never edited
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Note {
+    pub text: String,
+    pub created_at: NaiveDateTime,
+}
+
+impl Note {
+    pub fn new(text: String, created_at: NaiveDateTime) -> Self {
+        Note { text, created_at }
+    }
+
+    // Case-insensitive substring match
+    pub fn contains(&self, needle: &str) -> bool {
+        self.text.to_lowercase().contains(&needle.to_lowercase())
+    }
+}
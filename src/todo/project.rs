@@ -0,0 +1,119 @@
+use crate::todo::{TodoError, TodoItem, TodoResult};
+use std::collections::BTreeMap;
+
+// Projects are dotted paths such as `work.backend`. A todo in a sub-project
+// also belongs to every project above it.
+
+// Normalise a project name to lowercase; "none" means no project
+pub fn parse_project(input: &str) -> TodoResult<Option<String>> {
+    let name = input.trim().to_lowercase();
+    if name == "none" {
+        return Ok(None);
+    }
+    let valid_segment = |segment: &str| {
+        !segment.is_empty()
+            && segment
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    };
+    if !name.split('.').all(valid_segment) {
+        return Err(TodoError::InvalidProject {
+            name: input.to_string(),
+        });
+    }
+    Ok(Some(name))
+}
+
+// Whether `project` is `filter` or one of its sub-projects
+pub fn in_project(project: Option<&str>, filter: &str) -> bool {
+    project.is_some_and(|p| {
+        p == filter
+            || p.strip_prefix(filter)
+                .is_some_and(|rest| rest.starts_with('.'))
+    })
+}
+
+// "work.backend.api" -> "work", "work.backend", "work.backend.api"
+pub fn project_paths(project: &str) -> impl Iterator<Item = &str> {
+    project
+        .match_indices('.')
+        .map(|(i, _)| &project[..i])
+        .chain(std::iter::once(project))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProjectCount {
+    pub open: usize,
+    pub total: usize,
+}
+
+// Todos per project, sorted by name. Counts include sub-projects.
+pub fn project_counts(todos: &[TodoItem]) -> BTreeMap<String, ProjectCount> {
+    let mut counts: BTreeMap<String, ProjectCount> = BTreeMap::new();
+    for todo in todos {
+        let Some(project) = todo.project() else {
+            continue;
+        };
+        for path in project_paths(project) {
+            let count = counts.entry(path.to_string()).or_default();
+            count.total += 1;
+            if !todo.is_closed() {
+                count.open += 1;
+            }
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn todo_in(id: u32, project: Option<&str>, done: bool) -> TodoItem {
+        TodoItem::new(id, "Task".to_string(), None, done, None, None, None)
+            .with_project(project.map(str::to_string))
+    }
+
+    #[test]
+    fn test_parse_project() {
+        assert_eq!(
+            parse_project(" Work.Backend ").unwrap(),
+            Some("work.backend".to_string())
+        );
+        assert_eq!(parse_project("none").unwrap(), None);
+        for input in ["", "work.", ".work", "work..api", "my work"] {
+            assert!(matches!(
+                parse_project(input),
+                Err(TodoError::InvalidProject { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_in_project() {
+        assert!(in_project(Some("work"), "work"));
+        assert!(in_project(Some("work.backend"), "work"));
+        assert!(!in_project(Some("workshop"), "work"));
+        assert!(!in_project(Some("work"), "work.backend"));
+        assert!(!in_project(None, "work"));
+    }
+
+    #[test]
+    fn test_project_counts() {
+        let todos = vec![
+            todo_in(1, Some("work.backend"), false),
+            todo_in(2, Some("work.backend.api"), true),
+            todo_in(3, Some("work"), false),
+            todo_in(4, Some("home"), false),
+            todo_in(5, None, false),
+        ];
+        let counts = project_counts(&todos);
+
+        assert_eq!(
+            counts.keys().collect::<Vec<_>>(),
+            vec!["home", "work", "work.backend", "work.backend.api"]
+        );
+        assert_eq!(counts["work"], ProjectCount { open: 2, total: 3 });
+        assert_eq!(counts["work.backend"], ProjectCount { open: 1, total: 2 });
+    }
+}
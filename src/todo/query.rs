@@ -0,0 +1,553 @@
+use crate::todo::project::{in_project, parse_project};
+use crate::todo::tag::{has_tag, normalize_tag};
+use crate::todo::uda::{Comparison, UdaFilter, Udas};
+use crate::todo::{Priority, Status, TodoError, TodoItem, TodoResult};
+use crate::utils::parse_past_date;
+use chrono::NaiveDate;
+
+// A `list --where` expression such as
+// `priority>=medium and (tag:work or due<friday) and not done`.
+// `and` binds tighter than `or`, and `not` tighter than both.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterExpr {
+    And(Box<FilterExpr>, Box<FilterExpr>),
+    Or(Box<FilterExpr>, Box<FilterExpr>),
+    Not(Box<FilterExpr>),
+    Condition(Condition),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Status(Status),
+    // Neither done nor cancelled
+    Open,
+    Overdue,
+    Priority(Comparison, Option<Priority>),
+    // Dates are compared by day
+    Date(DateField, Comparison, Option<NaiveDate>),
+    Tag(String),
+    // A project and its sub-projects; `None` matches todos without one
+    Project(Option<String>),
+    Assignee(Option<String>),
+    // Case-insensitive substring
+    Text(TextField, String),
+    Uda(UdaFilter),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateField {
+    Due,
+    Scheduled,
+    Wait,
+    Created,
+    Completed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextField {
+    Title,
+    Description,
+    Note,
+}
+
+impl FilterExpr {
+    pub fn parse(input: &str, udas: &Udas) -> TodoResult<Self> {
+        let mut parser = Parser {
+            query: input,
+            tokens: tokenize(input)?,
+            pos: 0,
+            udas,
+        };
+        let expr = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(token) => Err(parser.error(
+                token.column,
+                format!("expected 'and' or 'or' before {}", token.kind),
+            )),
+        }
+    }
+
+    pub fn matches(&self, item: &TodoItem) -> bool {
+        match self {
+            FilterExpr::And(a, b) => a.matches(item) && b.matches(item),
+            FilterExpr::Or(a, b) => a.matches(item) || b.matches(item),
+            FilterExpr::Not(expr) => !expr.matches(item),
+            FilterExpr::Condition(condition) => condition.matches(item),
+        }
+    }
+}
+
+impl Condition {
+    pub fn matches(&self, item: &TodoItem) -> bool {
+        match self {
+            Condition::Status(status) => item.status() == *status,
+            Condition::Open => !item.is_closed(),
+            Condition::Overdue => item.is_overdue(),
+            Condition::Priority(comparison, expected) => {
+                compare_option(item.priority(), *comparison, *expected)
+            }
+            Condition::Date(field, comparison, expected) => {
+                let actual = match field {
+                    DateField::Due => item.due_date(),
+                    DateField::Scheduled => item.scheduled(),
+                    DateField::Wait => item.wait_until(),
+                    DateField::Created => Some(item.created_at()),
+                    DateField::Completed => item.completed_at(),
+                };
+                compare_option(actual.map(|d| d.date()), *comparison, *expected)
+            }
+            Condition::Tag(tag) => has_tag(item, tag),
+            Condition::Project(Some(project)) => in_project(item.project(), project),
+            Condition::Project(None) => item.project().is_none(),
+            Condition::Assignee(assignee) => item.assignee() == assignee.as_deref(),
+            Condition::Text(field, text) => {
+                let text = text.to_lowercase();
+                let contains = |s: &str| s.to_lowercase().contains(&text);
+                match field {
+                    TextField::Title => contains(item.title()),
+                    TextField::Description => item.description().is_some_and(contains),
+                    TextField::Note => item.notes().iter().any(|note| contains(&note.text)),
+                }
+            }
+            Condition::Uda(filter) => filter.matches(item),
+        }
+    }
+}
+
+// Unset values only compare equal to each other
+fn compare_option<T: Ord>(actual: Option<T>, comparison: Comparison, expected: Option<T>) -> bool {
+    match (actual, expected) {
+        (Some(actual), Some(expected)) => comparison.holds(actual.cmp(&expected)),
+        (actual, expected) => {
+            let equal = actual.is_none() && expected.is_none();
+            match comparison {
+                Comparison::Equal => equal,
+                Comparison::NotEqual => !equal,
+                _ => false,
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Open,
+    Close,
+    Word(String),
+    Quoted(String),
+    // `:` or a comparison operator
+    Op(&'static str),
+}
+
+impl std::fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TokenKind::Open => write!(f, "'('"),
+            TokenKind::Close => write!(f, "')'"),
+            TokenKind::Word(word) => write!(f, "'{word}'"),
+            TokenKind::Quoted(text) => write!(f, "\"{text}\""),
+            TokenKind::Op(op) => write!(f, "'{op}'"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    // 1-based position of the first character
+    column: usize,
+}
+
+const OPERATORS: [&str; 7] = ["!=", "<=", ">=", "=", "<", ">", ":"];
+
+fn tokenize(input: &str) -> TodoResult<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let error = |column: usize, message: String| TodoError::InvalidQuery {
+        query: input.to_string(),
+        column,
+        message,
+    };
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let column = i + 1;
+        let c = chars[i];
+        let rest: String = chars[i..].iter().take(2).collect();
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let kind = if c == '(' || c == ')' {
+            i += 1;
+            if c == '(' {
+                TokenKind::Open
+            } else {
+                TokenKind::Close
+            }
+        } else if c == '"' || c == '\'' {
+            let end = chars[i + 1..]
+                .iter()
+                .position(|&q| q == c)
+                .ok_or_else(|| error(column, "unterminated quote".to_string()))?;
+            let text = chars[i + 1..i + 1 + end].iter().collect();
+            i += end + 2;
+            TokenKind::Quoted(text)
+        } else if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(*op)) {
+            i += op.len();
+            TokenKind::Op(op)
+        } else if c == '!' {
+            return Err(error(
+                column,
+                "unexpected '!', use 'not' or '!='".to_string(),
+            ));
+        } else {
+            let len = chars[i..]
+                .iter()
+                .position(|&c| c.is_whitespace() || "()<>=!:\"'".contains(c))
+                .unwrap_or(chars.len() - i);
+            let word = chars[i..i + len].iter().collect();
+            i += len;
+            TokenKind::Word(word)
+        };
+        tokens.push(Token { kind, column });
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    query: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    udas: &'a Udas,
+}
+
+impl Parser<'_> {
+    fn error(&self, column: usize, message: String) -> TodoError {
+        TodoError::InvalidQuery {
+            query: self.query.to_string(),
+            column,
+            message,
+        }
+    }
+
+    // Column just past the end of the query
+    fn end_column(&self) -> usize {
+        self.query.chars().count() + 1
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn at_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token { kind: TokenKind::Word(w), .. }) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn parse_or(&mut self) -> TodoResult<FilterExpr> {
+        let mut expr = self.parse_and()?;
+        while self.at_keyword("or") {
+            self.pos += 1;
+            expr = FilterExpr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> TodoResult<FilterExpr> {
+        let mut expr = self.parse_not()?;
+        while self.at_keyword("and") {
+            self.pos += 1;
+            expr = FilterExpr::And(Box::new(expr), Box::new(self.parse_not()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> TodoResult<FilterExpr> {
+        if self.at_keyword("not") {
+            self.pos += 1;
+            return Ok(FilterExpr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> TodoResult<FilterExpr> {
+        let Some(token) = self.next() else {
+            return Err(self.error(self.end_column(), "expected a condition".to_string()));
+        };
+        match token.kind {
+            TokenKind::Open => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::Close,
+                        ..
+                    }) => Ok(expr),
+                    Some(other) => {
+                        Err(self.error(other.column, format!("expected ')' before {}", other.kind)))
+                    }
+                    None => Err(self.error(token.column, "unclosed '('".to_string())),
+                }
+            }
+            TokenKind::Word(word) => {
+                let keyword = ["and", "or"].iter().any(|k| word.eq_ignore_ascii_case(k));
+                if keyword {
+                    return Err(self.error(
+                        token.column,
+                        format!("expected a condition before '{word}'"),
+                    ));
+                }
+                match self.peek() {
+                    Some(Token {
+                        kind: TokenKind::Op(op),
+                        column,
+                    }) => {
+                        let (op, op_column) = (*op, *column);
+                        self.pos += 1;
+                        let value = match self.next() {
+                            Some(Token {
+                                kind: TokenKind::Word(value) | TokenKind::Quoted(value),
+                                column,
+                            }) => (value, column),
+                            _ => {
+                                return Err(self.error(
+                                    op_column + op.len(),
+                                    format!("expected a value after '{op}'"),
+                                ))
+                            }
+                        };
+                        self.condition((&word, token.column), op, value)
+                    }
+                    _ => self.keyword(&word, token.column),
+                }
+            }
+            other => Err(self.error(token.column, format!("expected a condition, found {other}"))),
+        }
+    }
+
+    // A bare word such as `done` or `overdue`
+    fn keyword(&self, word: &str, column: usize) -> TodoResult<FilterExpr> {
+        let condition = match word.to_lowercase().as_str() {
+            "open" => Condition::Open,
+            "closed" => return Ok(FilterExpr::Not(Box::new(condition_expr(Condition::Open)))),
+            "overdue" => Condition::Overdue,
+            _ => match word.parse::<Status>() {
+                Ok(status) => Condition::Status(status),
+                Err(_) => {
+                    return Err(self.error(
+                        column,
+                        format!("unknown keyword '{word}'; use a status, open, closed or overdue, or a condition like tag:work"),
+                    ))
+                }
+            },
+        };
+        Ok(condition_expr(condition))
+    }
+
+    fn condition(
+        &self,
+        (field, field_column): (&str, usize),
+        op: &str,
+        (value, value_column): (String, usize),
+    ) -> TodoResult<FilterExpr> {
+        let comparison = match op {
+            "!=" => Comparison::NotEqual,
+            "<" => Comparison::Less,
+            "<=" => Comparison::LessOrEqual,
+            ">" => Comparison::Greater,
+            ">=" => Comparison::GreaterOrEqual,
+            _ => Comparison::Equal,
+        };
+        let invalid = |message: String| self.error(value_column, message);
+        let none = value.eq_ignore_ascii_case("none");
+
+        let name = field.to_lowercase();
+        let (condition, equality_only) = match name.as_str() {
+            "status" => match value.parse::<Status>() {
+                Ok(status) => (Condition::Status(status), true),
+                Err(_) => return Err(invalid(format!("unknown status '{value}'"))),
+            },
+            "priority" | "pri" => {
+                let priority = match none {
+                    true => None,
+                    false => Some(value.parse::<Priority>().map_err(|_| {
+                        invalid(format!(
+                            "unknown priority '{value}'; use high, medium, low or none"
+                        ))
+                    })?),
+                };
+                (Condition::Priority(comparison, priority), false)
+            }
+            "due" | "scheduled" | "wait" | "created" | "completed" => {
+                let field = match name.as_str() {
+                    "due" => DateField::Due,
+                    "scheduled" => DateField::Scheduled,
+                    "wait" => DateField::Wait,
+                    "created" => DateField::Created,
+                    _ => DateField::Completed,
+                };
+                let date = match none {
+                    true => None,
+                    false => match parse_past_date(Some(&value)) {
+                        Ok(Some(date)) => Some(date.date()),
+                        _ => return Err(invalid(format!("invalid date '{value}'"))),
+                    },
+                };
+                (Condition::Date(field, comparison, date), false)
+            }
+            "tag" | "tags" => (Condition::Tag(normalize_tag(&value)), true),
+            "project" => match parse_project(&value) {
+                Ok(project) => (Condition::Project(project), true),
+                Err(_) => return Err(invalid(format!("invalid project '{value}'"))),
+            },
+            "assignee" | "owner" => {
+                let assignee = (!none).then(|| value.trim().to_string());
+                (Condition::Assignee(assignee), true)
+            }
+            "title" => (Condition::Text(TextField::Title, value), true),
+            "description" | "desc" => (Condition::Text(TextField::Description, value), true),
+            "note" | "notes" => (Condition::Text(TextField::Note, value), true),
+            _ => {
+                let Some((key, def)) = self.udas.get_key_value(field) else {
+                    return Err(self.error(field_column, format!("unknown field '{field}'")));
+                };
+                let value = match value.trim() {
+                    "" => None,
+                    raw => Some(
+                        def.parse_value(key, raw)
+                            .map_err(|e| invalid(e.to_string()))?,
+                    ),
+                };
+                if value.is_none()
+                    && !matches!(comparison, Comparison::Equal | Comparison::NotEqual)
+                {
+                    return Err(invalid(format!("'{op}' needs a value")));
+                }
+                let filter = UdaFilter {
+                    key: key.clone(),
+                    def: def.clone(),
+                    comparison,
+                    value,
+                };
+                return Ok(condition_expr(Condition::Uda(filter)));
+            }
+        };
+
+        if !equality_only {
+            return Ok(condition_expr(condition));
+        }
+        match comparison {
+            Comparison::Equal => Ok(condition_expr(condition)),
+            Comparison::NotEqual => Ok(FilterExpr::Not(Box::new(condition_expr(condition)))),
+            _ => Err(self.error(
+                field_column + field.chars().count(),
+                format!("'{op}' does not apply to {name}; use ':' or '!='"),
+            )),
+        }
+    }
+}
+
+fn condition_expr(condition: Condition) -> FilterExpr {
+    FilterExpr::Condition(condition)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::todo::{UdaDef, UdaType};
+    use chrono::{Duration, Local};
+
+    fn parse(input: &str) -> TodoResult<FilterExpr> {
+        let udas = Udas::from([(
+            "points".to_string(),
+            UdaDef {
+                kind: UdaType::Number,
+                values: Vec::new(),
+                label: None,
+            },
+        )]);
+        FilterExpr::parse(input, &udas)
+    }
+
+    fn todo(id: u32, priority: Option<Priority>, tags: &[&str]) -> TodoItem {
+        let tags = (!tags.is_empty()).then(|| tags.iter().map(|t| t.to_string()).collect());
+        TodoItem::new(id, format!("Task {id}"), None, false, None, priority, tags)
+    }
+
+    fn error_column(input: &str) -> usize {
+        match parse(input) {
+            Err(TodoError::InvalidQuery { column, .. }) => column,
+            other => panic!("expected a query error for {input:?}, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_precedence() {
+        let expr = parse("done or tag:work and not priority=high").unwrap();
+        let FilterExpr::Or(left, right) = expr else {
+            panic!("or should bind loosest");
+        };
+        assert_eq!(*left, condition_expr(Condition::Status(Status::Done)));
+        assert!(matches!(*right, FilterExpr::And(_, ref r) if matches!(**r, FilterExpr::Not(_))));
+    }
+
+    #[test]
+    fn test_matches() {
+        let tomorrow = Local::now().naive_local() + Duration::days(1);
+        let mut urgent = todo(1, Some(Priority::High), &["Work"]);
+        urgent.set_due_date(Some(tomorrow));
+        let mut done = todo(2, Some(Priority::Medium), &["home"]);
+        done.set_status(Status::Done);
+        let mut pointed = todo(3, None, &[]);
+        pointed.set_uda("points", Some("5".to_string()));
+        let todos = [urgent, done, pointed];
+
+        let ids = |input: &str| {
+            let expr = parse(input).unwrap();
+            todos
+                .iter()
+                .filter(|t| expr.matches(t))
+                .map(|t| t.id())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ids("priority>=medium and (tag:work or due<friday) and not done"),
+            vec![1]
+        );
+        assert_eq!(ids("priority>=medium"), vec![1, 2]);
+        assert_eq!(ids("priority=none"), vec![3]);
+        assert_eq!(ids("due=none or tag!=work"), vec![2, 3]);
+        assert_eq!(ids("due<=tomorrow"), vec![1]);
+        assert_eq!(ids("points>3 or title:\"task 2\""), vec![2, 3]);
+        assert_eq!(ids("NOT open"), vec![2]);
+    }
+
+    #[test]
+    fn test_errors_point_at_column() {
+        assert_eq!(error_column("priority>=urgent"), 11);
+        assert_eq!(error_column("tag:work and"), 13);
+        assert_eq!(error_column("(done or open"), 1);
+        assert_eq!(error_column("done open"), 6);
+        assert_eq!(error_column("colour=red"), 1);
+        assert_eq!(error_column("tag<work"), 4);
+        assert_eq!(error_column("due<"), 5);
+        assert_eq!(error_column("title:'oops"), 7);
+        assert_eq!(error_column("someday"), 1);
+    }
+
+    #[test]
+    fn test_error_message() {
+        let error = parse("tag:work and due<later").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "❌ Invalid query at column 18: invalid date 'later'\n   tag:work and due<later\n                    ^"
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use crate::todo::{TodoError, TodoResult};
+use chrono::{Datelike, Duration, Months, NaiveDateTime, Weekday};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+// Repeat schedule of a todo, e.g. "every 2 weeks" or "every monday"
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    pub interval: u32,
+    // Only for weekly rules pinned to a day, such as "every friday"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weekday: Option<Weekday>,
+}
+
+impl Recurrence {
+    pub fn new(frequency: Frequency, interval: u32) -> Self {
+        Recurrence {
+            frequency,
+            interval,
+            weekday: None,
+        }
+    }
+
+    // First occurrence strictly after `from`, keeping the time of day
+    pub fn next_after(&self, from: NaiveDateTime) -> TodoResult<NaiveDateTime> {
+        let interval = self.interval.max(1);
+        let next = match (self.frequency, self.weekday) {
+            (Frequency::Daily, _) => add_days(from, i64::from(interval)),
+            (Frequency::Weekly, None) => add_days(from, 7 * i64::from(interval)),
+            (Frequency::Weekly, Some(weekday)) => {
+                let ahead = (weekday.num_days_from_monday() + 7
+                    - from.weekday().num_days_from_monday())
+                    % 7;
+                let ahead = if ahead == 0 { 7 } else { ahead };
+                add_days(from, i64::from(ahead) + 7 * i64::from(interval - 1))
+            }
+            // Months with fewer days clamp to their last day (31 Jan -> 28/29 Feb)
+            (Frequency::Monthly, _) => from.checked_add_months(Months::new(interval)),
+            (Frequency::Yearly, _) => interval
+                .checked_mul(12)
+                .and_then(|months| from.checked_add_months(Months::new(months))),
+        };
+        next.ok_or_else(|| TodoError::RecurrenceOutOfRange {
+            rule: self.to_string(),
+        })
+    }
+}
+
+fn add_days(from: NaiveDateTime, days: i64) -> Option<NaiveDateTime> {
+    Duration::try_days(days).and_then(|days| from.checked_add_signed(days))
+}
+
+fn parse_weekday(input: &str) -> Option<Weekday> {
+    match input.trim_end_matches('s') {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn parse_unit(input: &str) -> Option<Frequency> {
+    match input {
+        "day" | "days" => Some(Frequency::Daily),
+        "week" | "weeks" => Some(Frequency::Weekly),
+        "month" | "months" => Some(Frequency::Monthly),
+        "year" | "years" => Some(Frequency::Yearly),
+        _ => None,
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = TodoError;
+
+    // Accepts "daily", "weekly", "monthly", "yearly", "every <unit>",
+    // "every <n> <units>", "every other <unit>", "every <weekday>" and
+    // "every <n> weeks on <weekday>"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let input = s.trim().to_lowercase();
+        let invalid = || TodoError::InvalidRecurrence {
+            input: s.to_string(),
+        };
+
+        let words: Vec<&str> = input.split_whitespace().collect();
+        let rule = match words.as_slice() {
+            ["daily"] => Recurrence::new(Frequency::Daily, 1),
+            ["weekly"] => Recurrence::new(Frequency::Weekly, 1),
+            ["monthly"] => Recurrence::new(Frequency::Monthly, 1),
+            ["yearly" | "annually"] => Recurrence::new(Frequency::Yearly, 1),
+            ["every", word] => match (parse_unit(word), parse_weekday(word)) {
+                (Some(frequency), _) => Recurrence::new(frequency, 1),
+                (None, Some(weekday)) => Recurrence {
+                    weekday: Some(weekday),
+                    ..Recurrence::new(Frequency::Weekly, 1)
+                },
+                (None, None) => return Err(invalid()),
+            },
+            ["every", "other", unit] => Recurrence::new(parse_unit(unit).ok_or_else(invalid)?, 2),
+            ["every", count, unit] => {
+                let interval: u32 = count.parse().map_err(|_| invalid())?;
+                if interval == 0 {
+                    return Err(invalid());
+                }
+                Recurrence::new(parse_unit(unit).ok_or_else(invalid)?, interval)
+            }
+            ["every", count, "weeks", "on", day] => Recurrence {
+                weekday: Some(parse_weekday(day).ok_or_else(invalid)?),
+                ..format!("every {count} weeks").parse()?
+            },
+            _ => return Err(invalid()),
+        };
+        Ok(rule)
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unit = match self.frequency {
+            Frequency::Daily => "day",
+            Frequency::Weekly => "week",
+            Frequency::Monthly => "month",
+            Frequency::Yearly => "year",
+        };
+        match (self.weekday, self.interval) {
+            (Some(weekday), 1) => write!(f, "every {weekday}"),
+            (Some(weekday), n) => write!(f, "every {n} weeks on {weekday}"),
+            (None, 1) => write!(f, "every {unit}"),
+            (None, n) => write!(f, "every {n} {unit}s"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(year: i32, month: u32, day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(9, 30, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_recurrence() {
+        let weekly = Recurrence::new(Frequency::Weekly, 1);
+        assert_eq!("weekly".parse::<Recurrence>().unwrap(), weekly);
+        assert_eq!("Every Week".parse::<Recurrence>().unwrap(), weekly);
+        assert_eq!(
+            "every 2 weeks".parse::<Recurrence>().unwrap(),
+            Recurrence::new(Frequency::Weekly, 2)
+        );
+        assert_eq!(
+            "every other month".parse::<Recurrence>().unwrap(),
+            Recurrence::new(Frequency::Monthly, 2)
+        );
+        assert_eq!(
+            "every monday".parse::<Recurrence>().unwrap().weekday,
+            Some(Weekday::Mon)
+        );
+
+        for input in [
+            "",
+            "sometimes",
+            "every",
+            "every 0 days",
+            "every 2 fortnights",
+        ] {
+            assert!(matches!(
+                input.parse::<Recurrence>(),
+                Err(TodoError::InvalidRecurrence { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_next_after() {
+        // 2025-03-05 is a Wednesday
+        let from = at(2025, 3, 5);
+        let rule = |s: &str| s.parse::<Recurrence>().unwrap();
+
+        assert_eq!(rule("daily").next_after(from).unwrap(), at(2025, 3, 6));
+        assert_eq!(
+            rule("every 2 weeks").next_after(from).unwrap(),
+            at(2025, 3, 19)
+        );
+        assert_eq!(
+            rule("every monday").next_after(from).unwrap(),
+            at(2025, 3, 10)
+        );
+        assert_eq!(
+            rule("every wednesday").next_after(from).unwrap(),
+            at(2025, 3, 12)
+        );
+        assert_eq!(
+            rule("monthly").next_after(at(2025, 1, 31)).unwrap(),
+            at(2025, 2, 28)
+        );
+        assert_eq!(rule("yearly").next_after(from).unwrap(), at(2026, 3, 5));
+    }
+
+    #[test]
+    fn test_next_after_out_of_range() {
+        let from = at(2025, 3, 5);
+        for input in [
+            "every 100000000 days",
+            "every 4000000000 weeks",
+            "every 400000000 years",
+        ] {
+            assert!(matches!(
+                input.parse::<Recurrence>().unwrap().next_after(from),
+                Err(TodoError::RecurrenceOutOfRange { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_display_roundtrip() {
+        let inputs = [
+            "every day",
+            "every 3 weeks",
+            "every Mon",
+            "every month",
+            "every 2 weeks on friday",
+        ];
+        for input in inputs {
+            let rule: Recurrence = input.parse().unwrap();
+            assert_eq!(rule.to_string().parse::<Recurrence>().unwrap(), rule);
+        }
+    }
+}
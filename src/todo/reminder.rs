@@ -0,0 +1,133 @@
+use crate::todo::{TodoError, TodoResult};
+use crate::utils::{parse_due_date, parse_duration};
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+// A point in time to be nudged about a todo
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Reminder {
+    pub at: NaiveDateTime,
+    // Minutes before the due date, so the reminder follows it when it moves
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before_due: Option<i64>,
+    // Set once the reminder has been shown by `todo reminders --pending`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub delivered: bool,
+}
+
+impl Reminder {
+    pub fn at(at: NaiveDateTime) -> Self {
+        Reminder {
+            at,
+            before_due: None,
+            delivered: false,
+        }
+    }
+
+    // "1h before" the `due` date, or any date accepted by `parse_due_date`
+    pub fn parse(input: &str, due: Option<NaiveDateTime>) -> TodoResult<Self> {
+        let trimmed = input.trim().to_lowercase();
+        if let Some(offset) = trimmed.strip_suffix("before") {
+            let offset = parse_duration(offset)?;
+            let due = due.ok_or_else(|| TodoError::ReminderWithoutDue {
+                input: input.to_string(),
+            })?;
+            let at = due
+                .checked_sub_signed(offset)
+                .ok_or_else(|| TodoError::DateOutOfRange {
+                    input: input.to_string(),
+                })?;
+            return Ok(Reminder {
+                at,
+                before_due: Some(offset.num_minutes()),
+                delivered: false,
+            });
+        }
+
+        parse_due_date(Some(input))?
+            .map(Reminder::at)
+            .ok_or_else(|| TodoError::InvalidDateFormat {
+                input: input.to_string(),
+            })
+    }
+
+    // Fired and not shown yet
+    pub fn is_pending(&self, now: NaiveDateTime) -> bool {
+        !self.delivered && self.at <= now
+    }
+
+    // Move a reminder that is relative to the due date along with it
+    pub fn follow_due(&mut self, due: Option<NaiveDateTime>) {
+        let (Some(minutes), Some(due)) = (self.before_due, due) else {
+            return;
+        };
+        let Some(at) = Duration::try_minutes(minutes).and_then(|d| due.checked_sub_signed(d))
+        else {
+            return;
+        };
+        if at != self.at {
+            self.at = at;
+            self.delivered = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2030, 3, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_relative_to_due() {
+        let reminder = Reminder::parse("2h before", Some(at(5, 12))).unwrap();
+        assert_eq!(reminder.at, at(5, 10));
+        assert_eq!(reminder.before_due, Some(120));
+
+        assert!(matches!(
+            Reminder::parse("1h before", None),
+            Err(TodoError::ReminderWithoutDue { .. })
+        ));
+        assert!(Reminder::parse("soon before", Some(at(5, 12))).is_err());
+        assert!(matches!(
+            Reminder::parse("1000000000w before", Some(at(5, 12))),
+            Err(TodoError::DateOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_absolute() {
+        let reminder = Reminder::parse("04-03-2030 09:00", Some(at(5, 12))).unwrap();
+        assert_eq!(reminder.at, at(4, 9));
+        assert_eq!(reminder.before_due, None);
+        assert!(Reminder::parse("whenever", None).is_err());
+    }
+
+    #[test]
+    fn test_follow_due() {
+        let mut relative = Reminder::parse("1d before", Some(at(5, 12))).unwrap();
+        relative.delivered = true;
+        relative.follow_due(Some(at(8, 12)));
+        assert_eq!(relative.at, at(7, 12));
+        assert!(!relative.delivered);
+
+        let mut absolute = Reminder::at(at(4, 9));
+        absolute.follow_due(Some(at(8, 12)));
+        assert_eq!(absolute.at, at(4, 9));
+    }
+
+    #[test]
+    fn test_is_pending() {
+        let mut reminder = Reminder::at(at(5, 9));
+        assert!(!reminder.is_pending(at(5, 8)));
+        assert!(reminder.is_pending(at(5, 9)));
+        reminder.delivered = true;
+        assert!(!reminder.is_pending(at(5, 10)));
+    }
+}
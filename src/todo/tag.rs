@@ -0,0 +1,96 @@
+use crate::todo::TodoItem;
+use std::collections::BTreeMap;
+
+// Trim and lowercase tags, dropping empty ones and duplicates while keeping
+// the first occurrence. No tags at all becomes `None`.
+pub fn normalize_tags(tags: Vec<String>) -> Option<Vec<String>> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = normalize_tag(&tag);
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    (!normalized.is_empty()).then_some(normalized)
+}
+
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+// Whether the todo carries `tag`; tags saved before normalisation still match
+pub fn has_tag(item: &TodoItem, tag: &str) -> bool {
+    item.tags()
+        .is_some_and(|tags| tags.iter().any(|t| normalize_tag(t) == tag))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TagCount {
+    pub open: usize,
+    pub done: usize,
+}
+
+// Open and done todos per tag, sorted by tag
+pub fn tag_counts(todos: &[TodoItem]) -> BTreeMap<String, TagCount> {
+    let mut counts: BTreeMap<String, TagCount> = BTreeMap::new();
+    for todo in todos {
+        let tags = normalize_tags(todo.tags().map(<[String]>::to_vec).unwrap_or_default());
+        for tag in tags.unwrap_or_default() {
+            let count = counts.entry(tag).or_default();
+            if todo.completed() {
+                count.done += 1;
+            } else if !todo.is_closed() {
+                count.open += 1;
+            }
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_normalize_tags() {
+        assert_eq!(
+            normalize_tags(strings(&[" Work", "urgent", "WORK", "", "  "])),
+            Some(strings(&["work", "urgent"]))
+        );
+        assert_eq!(normalize_tags(strings(&[" "])), None);
+        assert_eq!(normalize_tags(Vec::new()), None);
+    }
+
+    #[test]
+    fn test_tag_counts() {
+        let todo = |id, done, tags: &[&str]| {
+            TodoItem::new(
+                id,
+                "Task".to_string(),
+                None,
+                done,
+                None,
+                None,
+                Some(strings(tags)),
+            )
+        };
+        let todos = vec![
+            todo(1, false, &["work", "urgent"]),
+            todo(2, true, &["Work"]),
+            todo(3, false, &["home"]),
+        ];
+        let counts = tag_counts(&todos);
+
+        assert_eq!(
+            counts.keys().collect::<Vec<_>>(),
+            vec!["home", "urgent", "work"]
+        );
+        assert_eq!(counts["work"], TagCount { open: 1, done: 1 });
+        assert!(has_tag(&todos[1], "work"));
+        assert!(!has_tag(&todos[2], "work"));
+    }
+}
@@ -0,0 +1,76 @@
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+// One stretch of work on a todo, recorded when its timer is stopped
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct TimeEntry {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl TimeEntry {
+    pub fn new(start: NaiveDateTime, end: NaiveDateTime) -> Self {
+        TimeEntry { start, end }
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    // Part of the entry at or after `since`
+    pub fn duration_since(&self, since: NaiveDateTime) -> Duration {
+        (self.end - self.start.max(since)).max(Duration::zero())
+    }
+}
+
+// The running timer; only one exists at a time and it is kept in the
+// store's metadata so it survives between invocations
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct ActiveTimer {
+    pub id: u32,
+    pub started_at: NaiveDateTime,
+}
+
+// "2h 05m", "45m" or "0m"
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{m}m"),
+        (h, m) => format!("{h}h {m:02}m"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(hour: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 3, 5)
+            .unwrap()
+            .and_hms_opt(hour, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_entry_durations() {
+        let entry = TimeEntry::new(at(9, 0), at(10, 30));
+        assert_eq!(entry.duration(), Duration::try_minutes(90).unwrap());
+        assert_eq!(
+            entry.duration_since(at(10, 0)),
+            Duration::try_minutes(30).unwrap()
+        );
+        assert_eq!(entry.duration_since(at(8, 0)), entry.duration());
+        assert_eq!(entry.duration_since(at(11, 0)), Duration::zero());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::zero()), "0m");
+        assert_eq!(format_duration(Duration::try_minutes(45).unwrap()), "45m");
+        assert_eq!(
+            format_duration(Duration::try_minutes(125).unwrap()),
+            "2h 05m"
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use crate::todo::{TodoError, TodoItem, TodoResult};
+use crate::utils::parse_past_date;
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+// Format of date values, which keeps them sortable as plain text
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+// User-defined attributes by name, as declared in the config file
+pub type Udas = BTreeMap<String, UdaDef>;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UdaType {
+    String,
+    Number,
+    Date,
+    Enum,
+}
+
+// Declaration of one user-defined attribute, e.g.
+//
+//   [uda.size]
+//   type = "enum"
+//   values = ["S", "M", "L"]
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct UdaDef {
+    #[serde(rename = "type")]
+    pub kind: UdaType,
+    // Allowed values of an enum, in sort order
+    #[serde(default)]
+    pub values: Vec<String>,
+    // Column header; defaults to the attribute name
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl UdaDef {
+    // Check `raw` against the type and return the text stored on the todo
+    pub fn parse_value(&self, key: &str, raw: &str) -> TodoResult<String> {
+        let raw = raw.trim();
+        let invalid = |expected: String| TodoError::InvalidUdaValue {
+            key: key.to_string(),
+            value: raw.to_string(),
+            expected,
+        };
+
+        match self.kind {
+            UdaType::String => Ok(raw.to_string()),
+            UdaType::Number => raw
+                .parse::<f64>()
+                .ok()
+                .filter(|n| n.is_finite())
+                .map(|n| n.to_string())
+                .ok_or_else(|| invalid("a number".to_string())),
+            UdaType::Date => match parse_past_date(Some(raw)) {
+                Ok(Some(date)) => Ok(date.format(DATE_FORMAT).to_string()),
+                _ => Err(invalid("a date".to_string())),
+            },
+            UdaType::Enum => self
+                .values
+                .iter()
+                .find(|v| v.eq_ignore_ascii_case(raw))
+                .cloned()
+                .ok_or_else(|| invalid(format!("one of {}", self.values.join(", ")))),
+        }
+    }
+
+    // Order two stored values by their type: numerically, chronologically,
+    // by declaration order for enums and case-insensitively for text
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        match self.kind {
+            UdaType::String => a.to_lowercase().cmp(&b.to_lowercase()),
+            UdaType::Number => {
+                let number = |s: &str| s.parse::<f64>().unwrap_or(f64::NAN);
+                number(a).total_cmp(&number(b))
+            }
+            UdaType::Date => {
+                let date = |s: &str| NaiveDateTime::parse_from_str(s, DATE_FORMAT).ok();
+                date(a).cmp(&date(b))
+            }
+            UdaType::Enum => {
+                let index = |s: &str| self.values.iter().position(|v| v == s);
+                index(a).cmp(&index(b))
+            }
+        }
+    }
+
+    pub fn label<'a>(&'a self, key: &'a str) -> &'a str {
+        self.label.as_deref().unwrap_or(key)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    // Whether a value ordered `ordering` against the expected one passes
+    pub fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Equal => ordering == Ordering::Equal,
+            Comparison::NotEqual => ordering != Ordering::Equal,
+            Comparison::Less => ordering == Ordering::Less,
+            Comparison::LessOrEqual => ordering != Ordering::Greater,
+            Comparison::Greater => ordering == Ordering::Greater,
+            Comparison::GreaterOrEqual => ordering != Ordering::Less,
+        }
+    }
+}
+
+// A `list --uda` condition such as `points>=3` or `customer=acme`.
+// `key=` matches todos without a value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UdaFilter {
+    pub key: String,
+    pub def: UdaDef,
+    pub comparison: Comparison,
+    pub value: Option<String>,
+}
+
+impl UdaFilter {
+    pub fn parse(input: &str, udas: &Udas) -> TodoResult<Self> {
+        const OPERATORS: [(&str, Comparison); 6] = [
+            ("!=", Comparison::NotEqual),
+            ("<=", Comparison::LessOrEqual),
+            (">=", Comparison::GreaterOrEqual),
+            ("=", Comparison::Equal),
+            ("<", Comparison::Less),
+            (">", Comparison::Greater),
+        ];
+
+        let (position, operator, comparison) = OPERATORS
+            .iter()
+            .filter_map(|(op, cmp)| input.find(op).map(|pos| (pos, *op, *cmp)))
+            .min_by_key(|(pos, op, _)| (*pos, std::cmp::Reverse(op.len())))
+            .ok_or_else(|| TodoError::InvalidUdaFilter {
+                input: input.to_string(),
+            })?;
+
+        let key = input[..position].trim().to_string();
+        let raw = &input[position + operator.len()..];
+        let def = udas
+            .get(&key)
+            .cloned()
+            .ok_or_else(|| TodoError::UnknownUda { key: key.clone() })?;
+        let value = match raw.trim() {
+            "" => None,
+            raw => Some(def.parse_value(&key, raw)?),
+        };
+        if value.is_none() && !matches!(comparison, Comparison::Equal | Comparison::NotEqual) {
+            return Err(TodoError::InvalidUdaFilter {
+                input: input.to_string(),
+            });
+        }
+
+        Ok(UdaFilter {
+            key,
+            def,
+            comparison,
+            value,
+        })
+    }
+
+    pub fn matches(&self, item: &TodoItem) -> bool {
+        let actual = item.uda(&self.key);
+        let (actual, expected) = match (actual, self.value.as_deref()) {
+            (Some(actual), Some(expected)) => (actual, expected),
+            // Only equality applies when either side is unset
+            (actual, expected) => {
+                let equal = actual.is_none() && expected.is_none();
+                return match self.comparison {
+                    Comparison::Equal => equal,
+                    Comparison::NotEqual => !equal,
+                    _ => false,
+                };
+            }
+        };
+
+        self.comparison.holds(self.def.compare(actual, expected))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn def(kind: UdaType, values: &[&str]) -> UdaDef {
+        UdaDef {
+            kind,
+            values: values.iter().map(|v| v.to_string()).collect(),
+            label: None,
+        }
+    }
+
+    fn udas() -> Udas {
+        Udas::from([
+            ("customer".to_string(), def(UdaType::String, &[])),
+            ("points".to_string(), def(UdaType::Number, &[])),
+            ("sprint_end".to_string(), def(UdaType::Date, &[])),
+            ("size".to_string(), def(UdaType::Enum, &["S", "M", "L"])),
+        ])
+    }
+
+    #[test]
+    fn test_parse_value() {
+        let udas = udas();
+        assert_eq!(udas["points"].parse_value("points", "3.0").unwrap(), "3");
+        assert_eq!(udas["size"].parse_value("size", "m").unwrap(), "M");
+        assert_eq!(
+            udas["sprint_end"]
+                .parse_value("sprint_end", "2025-03-01")
+                .unwrap(),
+            "2025-03-01 00:00"
+        );
+
+        for (key, raw) in [
+            ("points", "many"),
+            ("size", "XL"),
+            ("sprint_end", "soonish"),
+        ] {
+            assert!(matches!(
+                udas[key].parse_value(key, raw),
+                Err(TodoError::InvalidUdaValue { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let udas = udas();
+        assert_eq!(udas["points"].compare("10", "9"), Ordering::Greater);
+        assert_eq!(udas["size"].compare("S", "L"), Ordering::Less);
+        assert_eq!(udas["customer"].compare("acme", "ACME"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_filter() {
+        let udas = udas();
+        let mut todo = TodoItem::new(1, "Task".to_string(), None, false, None, None, None);
+        todo.set_uda("points", Some("5".to_string()));
+        todo.set_uda("size", Some("M".to_string()));
+
+        let matches = |input: &str| UdaFilter::parse(input, &udas).unwrap().matches(&todo);
+        assert!(matches("points>=5"));
+        assert!(!matches("points<5"));
+        assert!(matches("size>S"));
+        assert!(matches("customer="));
+        assert!(!matches("customer!="));
+        assert!(!matches("customer=acme"));
+        assert!(matches("customer!=acme"));
+
+        assert!(matches!(
+            UdaFilter::parse("points", &udas),
+            Err(TodoError::InvalidUdaFilter { .. })
+        ));
+        assert!(matches!(
+            UdaFilter::parse("owner=me", &udas),
+            Err(TodoError::UnknownUda { .. })
+        ));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::todo::{TodoError, TodoResult};
-use chrono::{Local, NaiveDate, NaiveDateTime};
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
 use chrono_english::{parse_date_string, Dialect};
 
 const DATE_FORMATS: &[&str] = &[
@@ -13,6 +13,16 @@ const DATE_FORMATS: &[&str] = &[
 ];
 
 pub fn parse_due_date(due_str: Option<&str>) -> TodoResult<Option<NaiveDateTime>> {
+    parse_date(due_str, false)
+}
+
+// Same formats as `parse_due_date`, but relative dates may lie in the past
+// ("yesterday", "last week"), as needed by filters on completion time
+pub fn parse_past_date(date_str: Option<&str>) -> TodoResult<Option<NaiveDateTime>> {
+    parse_date(date_str, true)
+}
+
+fn parse_date(due_str: Option<&str>, allow_past: bool) -> TodoResult<Option<NaiveDateTime>> {
     let Some(date_str) = due_str else {
         return Ok(None);
     };
@@ -36,7 +46,7 @@ pub fn parse_due_date(due_str: Option<&str>) -> TodoResult<Option<NaiveDateTime>
     }
 
     if let Some(dt) = parse_relative_date(date_str)? {
-        if dt < Local::now().naive_local() {
+        if !allow_past && dt < Local::now().naive_local() {
             return Err(TodoError::InvalidDate {
                 input: date_str.to_string(),
                 reason: "Date cannot be in the past".to_string(),
@@ -50,6 +60,29 @@ pub fn parse_due_date(due_str: Option<&str>) -> TodoResult<Option<NaiveDateTime>
     })
 }
 
+// Parse a span such as "30d", "2w", "12h" or "45m"
+pub fn parse_duration(input: &str) -> TodoResult<Duration> {
+    let trimmed = input.trim().to_lowercase();
+    let split = trimmed
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(trimmed.len());
+    let (amount, unit) = trimmed.split_at(split);
+
+    let invalid = || TodoError::InvalidDuration {
+        input: input.to_string(),
+    };
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+
+    let duration = match unit.trim() {
+        "m" | "min" | "mins" | "minute" | "minutes" => Duration::try_minutes(amount),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Duration::try_hours(amount),
+        "d" | "day" | "days" => Duration::try_days(amount),
+        "w" | "week" | "weeks" => Duration::try_weeks(amount),
+        _ => None,
+    };
+    duration.ok_or_else(invalid)
+}
+
 fn parse_relative_date(input: &str) -> TodoResult<Option<NaiveDateTime>> {
     let now = Local::now().naive_local();
 
@@ -211,6 +244,24 @@ mod tests {
         assert!(result.unwrap().is_some());
     }
 
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30d").unwrap(), Duration::days(30));
+        assert_eq!(parse_duration("2w").unwrap(), Duration::weeks(2));
+        assert_eq!(parse_duration(" 12h ").unwrap(), Duration::hours(12));
+        assert_eq!(parse_duration("45 min").unwrap(), Duration::minutes(45));
+        assert_eq!(parse_duration("3 Days").unwrap(), Duration::days(3));
+
+        for input in ["", "d", "30", "30y", "-5d", "ten days", "99999999999999w"] {
+            match parse_duration(input) {
+                Err(TodoError::InvalidDuration { input: reported }) => {
+                    assert_eq!(reported, input)
+                }
+                other => panic!("Expected InvalidDuration for '{input}', got {other:?}"),
+            }
+        }
+    }
+
     #[test]
     fn test_parse_relative_date_internal() {
         let result = parse_relative_date("not a date at all xyz123");
@@ -221,4 +272,15 @@ mod tests {
         assert!(result.is_ok());
         assert!(result.unwrap().is_some());
     }
+
+    #[test]
+    fn test_parse_past_date() {
+        let yesterday = Local::now().naive_local().date() - Duration::try_days(1).unwrap();
+        assert_eq!(
+            parse_past_date(Some("yesterday")).unwrap(),
+            yesterday.and_hms_opt(0, 0, 0)
+        );
+        assert!(parse_due_date(Some("yesterday")).is_err());
+        assert!(parse_past_date(Some("not a date")).is_err());
+    }
 }
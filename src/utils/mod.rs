@@ -1,5 +1,7 @@
 pub mod date;
+pub mod open;
 pub mod validation;
 
-pub use date::parse_due_date;
+pub use date::{parse_due_date, parse_duration, parse_past_date};
+pub use open::open_target;
 pub use validation::validate_text;
@@ -0,0 +1,45 @@
+use crate::todo::{TodoError, TodoResult};
+use std::process::Command;
+
+#[cfg(target_os = "macos")]
+const SYSTEM_OPENER: &str = "open";
+#[cfg(not(target_os = "macos"))]
+const SYSTEM_OPENER: &str = "xdg-open";
+
+// Open a URL or file with the user's browser or the desktop's default
+// application. Does not wait for the program to exit.
+pub fn open_target(target: &str, is_url: bool) -> TodoResult<()> {
+    let program = opener(is_url, std::env::var("BROWSER").ok());
+    Command::new(&program)
+        .arg(target)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| TodoError::OpenFailed {
+            target: target.to_string(),
+            reason: format!("{program}: {e}"),
+        })
+}
+
+// URLs go to `$BROWSER` when it is set (the first entry if it is a
+// colon-separated list); everything else to the system opener
+fn opener(is_url: bool, browser: Option<String>) -> String {
+    browser
+        .filter(|_| is_url)
+        .and_then(|b| b.split(':').next().map(str::trim).map(str::to_string))
+        .filter(|b| !b.is_empty())
+        .unwrap_or_else(|| SYSTEM_OPENER.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opener() {
+        let browser = Some("firefox:chromium".to_string());
+        assert_eq!(opener(true, browser.clone()), "firefox");
+        assert_eq!(opener(false, browser), SYSTEM_OPENER);
+        assert_eq!(opener(true, None), SYSTEM_OPENER);
+        assert_eq!(opener(true, Some(String::new())), SYSTEM_OPENER);
+    }
+}